@@ -1,23 +1,38 @@
 const COMMANDS: &[&str] = &[
     "start_watch",
     "stop_watch",
+    "pause_watch",
+    "resume_watch",
+    "watch_status",
+    "get_last_change",
+    "subscribe",
+    "unsubscribe",
     "has_text",
     "has_rtf",
     "has_html",
     "has_image",
     "has_files",
+    "has_format",
     "read_text",
     "read_rtf",
     "read_html",
     "read_image",
     "read_files",
+    "read_format",
+    "read_all",
     "write_text",
     "write_rtf",
     "write_html",
     "write_image",
     "write_files",
+    "write_format",
+    "write",
     "clear",
+    "available_formats",
+    "change_count",
+    "fingerprint",
     "get_file_path",
+    "status",
 ];
 
 fn main() {
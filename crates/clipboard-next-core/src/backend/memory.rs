@@ -0,0 +1,227 @@
+use super::{ChangeHandler, ClipboardBackend, WatchHandle};
+use crate::models::{ClipboardFormat, ClipboardStatus, FormatInfo, Selection};
+use crate::{utils, Error, Result};
+use clipboard_rs::common::{ContentData, RustImage};
+use clipboard_rs::{ClipboardContent, ContentFormat, RustImageData};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// In-memory backend for tests and headless environments
+///
+/// Clones share the same clipboard, so a test can keep one clone to script "external" changes
+/// with [`MemoryBackend::set_external`] while the plugin uses the other.
+#[derive(Clone, Default)]
+pub struct MemoryBackend {
+    inner: Arc<MemoryInner>,
+}
+
+#[derive(Default)]
+struct MemoryInner {
+    contents: Mutex<Vec<ClipboardContent>>,
+    handlers: Mutex<Vec<(u64, ChangeHandler)>>,
+    next_handler_id: AtomicU64,
+    unavailable: Mutex<Option<String>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the contents as if another application wrote to the clipboard
+    pub fn set_external(&self, contents: Vec<ClipboardContent>) {
+        *self.inner.contents.lock() = contents;
+        self.notify();
+    }
+
+    /// Clear the contents as if another application cleared the clipboard
+    pub fn clear_external(&self) {
+        self.inner.contents.lock().clear();
+        self.notify();
+    }
+
+    /// Copy of the current contents
+    pub fn contents(&self) -> Vec<ClipboardContent> {
+        self.inner
+            .contents
+            .lock()
+            .iter()
+            .map(clone_content)
+            .collect()
+    }
+
+    /// Make every operation fail with [`Error::BackendUnavailable`] until `None` is passed
+    pub fn set_unavailable(&self, reason: Option<String>) {
+        *self.inner.unavailable.lock() = reason;
+    }
+
+    fn check_available(&self) -> Result<()> {
+        match self.inner.unavailable.lock().as_ref() {
+            Some(reason) => Err(Error::BackendUnavailable(reason.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Call every registered watch handler
+    pub fn notify(&self) {
+        for (_, handler) in self.inner.handlers.lock().iter_mut() {
+            handler();
+        }
+    }
+}
+
+impl ClipboardBackend for MemoryBackend {
+    fn has(&self, format: ContentFormat) -> Result<bool> {
+        self.check_available()?;
+
+        Ok(self
+            .inner
+            .contents
+            .lock()
+            .iter()
+            .any(|content| is_format(content, &format)))
+    }
+
+    fn get(&self, formats: &[ContentFormat]) -> Result<Vec<ClipboardContent>> {
+        self.check_available()?;
+
+        let contents = self.inner.contents.lock();
+
+        Ok(formats
+            .iter()
+            .filter_map(|format| contents.iter().find(|content| is_format(content, format)))
+            .map(clone_content)
+            .collect())
+    }
+
+    fn set(&self, contents: Vec<ClipboardContent>) -> Result<()> {
+        self.check_available()?;
+        self.set_external(contents);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.check_available()?;
+        self.clear_external();
+        Ok(())
+    }
+
+    fn available_formats(&self) -> Result<Vec<FormatInfo>> {
+        self.check_available()?;
+
+        Ok(self
+            .inner
+            .contents
+            .lock()
+            .iter()
+            .map(|content| {
+                let format = content.get_format();
+
+                FormatInfo {
+                    name: utils::format_name(&format),
+                    format: ClipboardFormat::from_content_format(&format),
+                    size: match content {
+                        ClipboardContent::Text(text)
+                        | ClipboardContent::Rtf(text)
+                        | ClipboardContent::Html(text) => Some(text.len() as u64),
+                        ClipboardContent::Other(_, buffer) => Some(buffer.len() as u64),
+                        ClipboardContent::Image(_) | ClipboardContent::Files(_) => None,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    fn get_buffer(&self, name: &str) -> Result<Vec<u8>> {
+        self.check_available()?;
+
+        let contents = self.inner.contents.lock();
+
+        let content = contents
+            .iter()
+            .find(|content| utils::format_name(&content.get_format()) == name)
+            .ok_or_else(|| Error::FormatUnavailable(name.to_string()))?;
+
+        Ok(match content {
+            ClipboardContent::Text(text)
+            | ClipboardContent::Rtf(text)
+            | ClipboardContent::Html(text) => text.clone().into_bytes(),
+            ClipboardContent::Image(image) => image
+                .to_png()
+                .map_err(|err| Error::ImageDecode(err.to_string()))?
+                .get_bytes()
+                .to_vec(),
+            ClipboardContent::Files(files) => files.join("\n").into_bytes(),
+            ClipboardContent::Other(_, buffer) => buffer.clone(),
+        })
+    }
+
+    /// Only the clipboard, shared with this backend
+    fn selection(&self, selection: Selection) -> Result<Box<dyn ClipboardBackend>> {
+        match selection {
+            Selection::Clipboard => Ok(Box::new(self.clone())),
+            selection => Err(Error::SelectionUnsupported(selection)),
+        }
+    }
+
+    fn watch(&self, handler: ChangeHandler) -> Result<WatchHandle> {
+        self.check_available()?;
+
+        let id = self.inner.next_handler_id.fetch_add(1, Ordering::Relaxed);
+
+        self.inner.handlers.lock().push((id, handler));
+
+        let inner = Arc::downgrade(&self.inner);
+
+        Ok(WatchHandle::new(move || {
+            if let Some(inner) = inner.upgrade() {
+                inner
+                    .handlers
+                    .lock()
+                    .retain(|(handler_id, _)| *handler_id != id);
+            }
+        }))
+    }
+
+    fn status(&self) -> ClipboardStatus {
+        let error = self.inner.unavailable.lock().clone();
+
+        ClipboardStatus {
+            available: error.is_none(),
+            error,
+        }
+    }
+}
+
+fn is_format(content: &ClipboardContent, format: &ContentFormat) -> bool {
+    match (content, format) {
+        (ClipboardContent::Text(_), ContentFormat::Text) => true,
+        (ClipboardContent::Rtf(_), ContentFormat::Rtf) => true,
+        (ClipboardContent::Html(_), ContentFormat::Html) => true,
+        (ClipboardContent::Image(_), ContentFormat::Image) => true,
+        (ClipboardContent::Files(_), ContentFormat::Files) => true,
+        (ClipboardContent::Other(name, _), ContentFormat::Other(format_name)) => {
+            name == format_name
+        }
+        _ => false,
+    }
+}
+
+fn clone_content(content: &ClipboardContent) -> ClipboardContent {
+    match content {
+        ClipboardContent::Text(text) => ClipboardContent::Text(text.clone()),
+        ClipboardContent::Rtf(rtf) => ClipboardContent::Rtf(rtf.clone()),
+        ClipboardContent::Html(html) => ClipboardContent::Html(html.clone()),
+        ClipboardContent::Image(image) => ClipboardContent::Image(
+            image
+                .get_dynamic_image()
+                .map(RustImageData::from_dynamic_image)
+                .unwrap_or_else(|_| RustImageData::empty()),
+        ),
+        ClipboardContent::Files(files) => ClipboardContent::Files(files.clone()),
+        ClipboardContent::Other(name, buffer) => {
+            ClipboardContent::Other(name.clone(), buffer.clone())
+        }
+    }
+}
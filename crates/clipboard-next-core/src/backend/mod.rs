@@ -0,0 +1,102 @@
+use crate::models::{ClipboardFormat, ClipboardStatus, FormatInfo, Selection};
+use crate::{Error, Result};
+use clipboard_rs::{ClipboardContent, ContentFormat};
+
+mod memory;
+mod system;
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "android",
+        target_os = "ios",
+        target_os = "emscripten"
+    ))
+))]
+mod x11;
+
+pub use memory::MemoryBackend;
+pub use system::SystemBackend;
+
+/// Callback invoked by a backend every time the clipboard changes
+pub type ChangeHandler = Box<dyn FnMut() + Send + 'static>;
+
+/// Storage the plugin reads from and writes to
+///
+/// [`SystemBackend`] talks to the system clipboard, [`MemoryBackend`] keeps everything in
+/// memory so commands can be exercised without a display server.
+pub trait ClipboardBackend: Send + Sync + 'static {
+    /// Check if the clipboard contains the given format
+    fn has(&self, format: ContentFormat) -> Result<bool>;
+
+    /// Get the contents of the given formats, formats that are not present are skipped
+    fn get(&self, formats: &[ContentFormat]) -> Result<Vec<ClipboardContent>>;
+
+    /// Replace the clipboard contents
+    fn set(&self, contents: Vec<ClipboardContent>) -> Result<()>;
+
+    /// Clear the clipboard contents
+    fn clear(&self) -> Result<()>;
+
+    /// List the formats the clipboard contains
+    ///
+    /// The default implementation only checks the formats with dedicated support.
+    fn available_formats(&self) -> Result<Vec<FormatInfo>> {
+        let mut formats = Vec::new();
+
+        for format in ClipboardFormat::ALL {
+            if self.has(format.into())? {
+                formats.push(FormatInfo {
+                    name: format.name().to_string(),
+                    format: Some(format),
+                    size: None,
+                });
+            }
+        }
+
+        Ok(formats)
+    }
+
+    /// Get the raw bytes of a format listed by [`ClipboardBackend::available_formats`]
+    fn get_buffer(&self, name: &str) -> Result<Vec<u8>> {
+        match self.get(&[ContentFormat::Other(name.to_string())])?.pop() {
+            Some(ClipboardContent::Other(_, buffer)) => Ok(buffer),
+            _ => Err(Error::FormatUnavailable(name.to_string())),
+        }
+    }
+
+    /// Backend for `selection`, e.g. the X11 `PRIMARY` selection
+    ///
+    /// The default implementation has no selections.
+    fn selection(&self, selection: Selection) -> Result<Box<dyn ClipboardBackend>> {
+        Err(Error::SelectionUnsupported(selection))
+    }
+
+    /// Call `handler` on every clipboard change until the returned handle is stopped
+    fn watch(&self, handler: ChangeHandler) -> Result<WatchHandle>;
+
+    /// Report whether the backend can currently reach the clipboard
+    fn status(&self) -> ClipboardStatus {
+        ClipboardStatus {
+            available: true,
+            error: None,
+        }
+    }
+}
+
+/// Handle to a running backend watcher
+pub struct WatchHandle(Box<dyn FnOnce() + Send + 'static>);
+
+impl WatchHandle {
+    pub fn new<F>(stop: F) -> Self
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        Self(Box::new(stop))
+    }
+
+    /// Stop the watcher
+    pub fn stop(self) {
+        (self.0)()
+    }
+}
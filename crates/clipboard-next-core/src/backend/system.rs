@@ -0,0 +1,233 @@
+use super::{ChangeHandler, ClipboardBackend, WatchHandle};
+use crate::models::{ClipboardFormat, ClipboardStatus, FormatInfo, Selection};
+use crate::{constants, Error, Result};
+use clipboard_rs::{
+    Clipboard, ClipboardContent, ClipboardContext, ClipboardHandler, ClipboardWatcher,
+    ClipboardWatcherContext, ContentFormat,
+};
+use parking_lot::Mutex;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+
+/// Backend using the system clipboard through [`clipboard_rs`]
+///
+/// The connection is established lazily: if the clipboard cannot be reached (e.g. no X server),
+/// operations fail with [`Error::BackendUnavailable`] and the connection is retried on later calls.
+pub struct SystemBackend {
+    state: Mutex<SystemState>,
+}
+
+#[derive(Default)]
+struct SystemState {
+    ctx: Option<ClipboardContext>,
+    error: Option<String>,
+    last_attempt: Option<Instant>,
+}
+
+struct SystemHandler(ChangeHandler);
+
+impl SystemBackend {
+    pub fn new() -> Self {
+        let mut state = SystemState::default();
+        state.connect();
+
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    fn with_ctx<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&ClipboardContext) -> Result<T>,
+    {
+        let mut state = self.state.lock();
+        state.connect();
+
+        match &state.ctx {
+            Some(ctx) => f(ctx),
+            None => Err(Error::BackendUnavailable(
+                state.error.clone().unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+impl Default for SystemBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemState {
+    /// Try to connect to the clipboard, at most once per retry interval
+    fn connect(&mut self) {
+        if self.ctx.is_some() {
+            return;
+        }
+
+        let retry_interval = Duration::from_millis(constants::backend::RETRY_INTERVAL);
+
+        if let Some(last_attempt) = self.last_attempt {
+            if last_attempt.elapsed() < retry_interval {
+                return;
+            }
+        }
+
+        self.last_attempt = Some(Instant::now());
+
+        match ClipboardContext::new() {
+            Ok(ctx) => {
+                self.ctx = Some(ctx);
+                self.error = None;
+            }
+            Err(err) => {
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+}
+
+impl ClipboardHandler for SystemHandler {
+    fn on_clipboard_change(&mut self) {
+        (self.0)()
+    }
+}
+
+impl ClipboardBackend for SystemBackend {
+    fn has(&self, format: ContentFormat) -> Result<bool> {
+        self.with_ctx(|ctx| Ok(ctx.has(format)))
+    }
+
+    fn get(&self, formats: &[ContentFormat]) -> Result<Vec<ClipboardContent>> {
+        self.with_ctx(|ctx| {
+            ctx.get(formats)
+                .map_err(|err| Error::Backend(err.to_string()))
+        })
+    }
+
+    fn set(&self, contents: Vec<ClipboardContent>) -> Result<()> {
+        self.with_ctx(|ctx| {
+            ctx.set(contents)
+                .map_err(|err| Error::Backend(err.to_string()))
+        })
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.with_ctx(|ctx| ctx.clear().map_err(|err| Error::Backend(err.to_string())))
+    }
+
+    /// Every format advertised by the clipboard owner, without sizes: reading a format may make the
+    /// owner convert its content, e.g. encode an image
+    fn available_formats(&self) -> Result<Vec<FormatInfo>> {
+        self.with_ctx(|ctx| {
+            let mut names = ctx
+                .available_formats()
+                .map_err(|err| Error::Backend(err.to_string()))?;
+
+            names.retain(|name| !constants::backend::META_TARGETS.contains(&name.as_str()));
+
+            Ok(names
+                .into_iter()
+                .map(|name| FormatInfo {
+                    format: ClipboardFormat::from_native(&name),
+                    size: None,
+                    name,
+                })
+                .collect())
+        })
+    }
+
+    fn get_buffer(&self, name: &str) -> Result<Vec<u8>> {
+        self.with_ctx(|ctx| {
+            ctx.get_buffer(name)
+                .map_err(|err| Error::Backend(err.to_string()))
+        })
+    }
+
+    /// On Linux the `PRIMARY` and `SECONDARY` selections are reached through X11 directly
+    fn selection(&self, selection: Selection) -> Result<Box<dyn ClipboardBackend>> {
+        match selection {
+            Selection::Clipboard => Ok(Box::new(SystemBackend::new())),
+            #[cfg(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "android",
+                    target_os = "ios",
+                    target_os = "emscripten"
+                ))
+            ))]
+            selection => Ok(Box::new(super::x11::SelectionBackend::new(selection)?)),
+            #[cfg(not(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "android",
+                    target_os = "ios",
+                    target_os = "emscripten"
+                ))
+            )))]
+            selection => Err(Error::SelectionUnsupported(selection)),
+        }
+    }
+
+    fn watch(&self, handler: ChangeHandler) -> Result<WatchHandle> {
+        let mut watcher = ClipboardWatcherContext::new()
+            .map_err(|err| Error::BackendUnavailable(err.to_string()))?;
+
+        let watcher_shutdown = watcher
+            .add_handler(SystemHandler(handler))
+            .get_shutdown_channel();
+
+        let (exited, exit) = channel::<String>();
+
+        spawn(move || {
+            // clipboard-rs panics when it cannot watch, e.g. without an X server or XFixes
+            let reason = match catch_unwind(AssertUnwindSafe(|| watcher.start_watch())) {
+                Ok(()) => "clipboard watcher stopped".to_string(),
+                Err(panic) => panic_message(panic),
+            };
+
+            let _ = exited.send(reason);
+        });
+
+        // the watcher gives no signal once it runs, but fails right away when it cannot
+        match exit.recv_timeout(Duration::from_millis(constants::backend::WATCH_STARTUP)) {
+            Err(RecvTimeoutError::Timeout) => Ok(WatchHandle::new(move || watcher_shutdown.stop())),
+            Ok(reason) => Err(Error::BackendUnavailable(reason)),
+            Err(RecvTimeoutError::Disconnected) => Err(Error::BackendUnavailable(
+                "clipboard watcher stopped".to_string(),
+            )),
+        }
+    }
+
+    /// Never waits for a running operation, which only holds the state for long once connected
+    fn status(&self) -> ClipboardStatus {
+        let Some(mut state) = self.state.try_lock() else {
+            return ClipboardStatus {
+                available: true,
+                error: None,
+            };
+        };
+
+        state.connect();
+
+        ClipboardStatus {
+            available: state.ctx.is_some(),
+            error: state.error.clone(),
+        }
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "clipboard watcher panicked".to_string(),
+        },
+    }
+}
@@ -0,0 +1,673 @@
+use super::{ChangeHandler, ClipboardBackend, WatchHandle};
+use crate::models::{ClipboardFormat, FormatInfo, Selection};
+use crate::{constants, Error, Result};
+use clipboard_rs::common::{RustImage, RustImageData};
+use clipboard_rs::{ClipboardContent, ContentFormat};
+use parking_lot::Mutex;
+use std::fmt::Display;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, spawn};
+use std::time::{Duration, Instant};
+use x11rb::connection::{Connection, RequestConnection as _};
+use x11rb::protocol::xfixes;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux, EventMask,
+    PropMode, Property, PropertyNotifyEvent, SelectionNotifyEvent, SelectionRequestEvent, Window,
+    WindowClass, SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{COPY_DEPTH_FROM_PARENT, CURRENT_TIME, NONE};
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        CLIPBOARD,
+        PRIMARY,
+        SECONDARY,
+        TARGETS,
+        INCR,
+        CLIPBOARD_NEXT,
+        UTF8_STRING,
+        STRING,
+        TEXT,
+        TEXT_PLAIN: b"text/plain",
+        TEXT_PLAIN_UTF8: b"text/plain;charset=utf-8",
+        RTF: b"text/rtf",
+        HTML: b"text/html",
+        PNG: b"image/png",
+        URI_LIST: b"text/uri-list",
+        GNOME_COPIED_FILES: b"x-special/gnome-copied-files",
+    }
+}
+
+const FILE_URI_PREFIX: &str = "file://";
+
+/// Size of a `ChangeProperty` request without its data, in bytes
+const CHANGE_PROPERTY_HEADER: usize = 24;
+
+/// Backend for the X11 `PRIMARY` and `SECONDARY` selections, which clipboard-rs cannot reach
+///
+/// Reads convert the selection on one connection, writes take ownership of it on another one
+/// whose thread answers the requests of other applications until the backend is dropped.
+pub(crate) struct SelectionBackend {
+    selection: Atom,
+    reader: Mutex<XConnection>,
+    owner: Arc<Owner>,
+}
+
+struct XConnection {
+    conn: RustConnection,
+    window: Window,
+    root: Window,
+    atoms: Atoms,
+}
+
+/// Contents served while this backend owns the selection
+struct Owner {
+    x: XConnection,
+    targets: Mutex<Vec<(Atom, Vec<u8>)>>,
+}
+
+/// Content too large for one request, sent in chunks with the `INCR` protocol
+struct Transfer {
+    requestor: Window,
+    property: Atom,
+    target: Atom,
+    data: Vec<u8>,
+    offset: usize,
+    updated_at: Instant,
+}
+
+impl SelectionBackend {
+    pub(crate) fn new(selection: Selection) -> Result<Self> {
+        let reader = XConnection::new()?;
+        let owner = Arc::new(Owner {
+            x: XConnection::new()?,
+            targets: Mutex::new(Vec::new()),
+        });
+
+        let selection = match selection {
+            Selection::Clipboard => reader.atoms.CLIPBOARD,
+            Selection::Primary => reader.atoms.PRIMARY,
+            Selection::Secondary => reader.atoms.SECONDARY,
+        };
+
+        let server = owner.clone();
+        spawn(move || server.serve(selection));
+
+        Ok(Self {
+            selection,
+            reader: Mutex::new(reader),
+            owner,
+        })
+    }
+
+    /// Get the content of `target`, `FormatUnavailable` if the owner cannot convert to it
+    fn read(&self, target: Atom) -> Result<Vec<u8>> {
+        let x = self.reader.lock();
+        let property = x.atoms.CLIPBOARD_NEXT;
+
+        x.conn
+            .convert_selection(x.window, self.selection, target, property, CURRENT_TIME)
+            .map_err(backend_error)?
+            .check()
+            .map_err(backend_error)?;
+
+        let timeout = Duration::from_millis(constants::backend::SELECTION_TIMEOUT);
+        let mut deadline = Instant::now() + timeout;
+        let mut buffer = Vec::new();
+        let mut incremental = false;
+
+        while Instant::now() < deadline {
+            let Some(event) = x.conn.poll_for_event().map_err(backend_error)? else {
+                thread::sleep(Duration::from_millis(
+                    constants::backend::SELECTION_POLL_INTERVAL,
+                ));
+                continue;
+            };
+
+            match event {
+                Event::SelectionNotify(event)
+                    if event.requestor == x.window
+                        && event.selection == self.selection
+                        && event.target == target =>
+                {
+                    if event.property == NONE {
+                        return Err(Error::FormatUnavailable(x.atom_name(target)));
+                    }
+
+                    let (kind, value) = x.take_property(property)?;
+
+                    // deleting the INCR property asks the owner for the first chunk
+                    if kind == x.atoms.INCR {
+                        incremental = true;
+                        continue;
+                    }
+
+                    return Ok(value);
+                }
+                Event::PropertyNotify(event)
+                    if incremental
+                        && event.window == x.window
+                        && event.atom == property
+                        && event.state == Property::NEW_VALUE =>
+                {
+                    let (_, chunk) = x.take_property(property)?;
+
+                    // an empty chunk ends the transfer
+                    if chunk.is_empty() {
+                        return Ok(buffer);
+                    }
+
+                    buffer.extend_from_slice(&chunk);
+                    deadline = Instant::now() + timeout;
+                }
+                _ => {}
+            }
+        }
+
+        Err(Error::Backend(format!(
+            "{} selection owner did not respond",
+            x.atom_name(self.selection)
+        )))
+    }
+
+    /// Targets advertised by the selection owner, empty if the selection has no owner
+    fn targets(&self) -> Result<Vec<Atom>> {
+        let targets = self.reader.lock().atoms.TARGETS;
+
+        match self.read(targets) {
+            Ok(data) => Ok(data
+                .chunks_exact(4)
+                .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .filter(|target| *target != targets)
+                .collect()),
+            Err(Error::FormatUnavailable(_)) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Targets holding `format`, most preferred first
+    fn candidates(&self, format: &ContentFormat) -> Result<Vec<Atom>> {
+        let x = self.reader.lock();
+        let atoms = &x.atoms;
+
+        Ok(match format {
+            ContentFormat::Text => vec![
+                atoms.UTF8_STRING,
+                atoms.TEXT_PLAIN_UTF8,
+                atoms.STRING,
+                atoms.TEXT_PLAIN,
+            ],
+            ContentFormat::Rtf => vec![atoms.RTF],
+            ContentFormat::Html => vec![atoms.HTML],
+            ContentFormat::Image => vec![atoms.PNG],
+            ContentFormat::Files => vec![atoms.URI_LIST],
+            ContentFormat::Other(name) => vec![x.atom(name)?],
+        })
+    }
+
+    /// Take ownership of the selection and serve `targets`
+    fn own(&self, targets: Vec<(Atom, Vec<u8>)>) -> Result<()> {
+        let x = &self.owner.x;
+        *self.owner.targets.lock() = targets;
+
+        x.conn
+            .set_selection_owner(x.window, self.selection, CURRENT_TIME)
+            .map_err(backend_error)?
+            .check()
+            .map_err(backend_error)?;
+
+        let owner = x
+            .conn
+            .get_selection_owner(self.selection)
+            .map_err(backend_error)?
+            .reply()
+            .map_err(backend_error)?
+            .owner;
+
+        if owner != x.window {
+            return Err(Error::Backend(format!(
+                "failed to take ownership of the {} selection",
+                x.atom_name(self.selection)
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SelectionBackend {
+    fn drop(&mut self) {
+        // ends the thread serving the selection
+        let x = &self.owner.x;
+        let _ = x.conn.destroy_window(x.window);
+        let _ = x.conn.flush();
+    }
+}
+
+impl XConnection {
+    fn new() -> Result<Self> {
+        let (conn, screen) = x11rb::connect(None).map_err(unavailable_error)?;
+        let root = conn.setup().roots[screen].root;
+        let root_visual = conn.setup().roots[screen].root_visual;
+        let window = conn.generate_id().map_err(unavailable_error)?;
+
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            root_visual,
+            &CreateWindowAux::new()
+                .event_mask(EventMask::STRUCTURE_NOTIFY | EventMask::PROPERTY_CHANGE),
+        )
+        .map_err(unavailable_error)?
+        .check()
+        .map_err(unavailable_error)?;
+
+        let atoms = Atoms::new(&conn)
+            .map_err(unavailable_error)?
+            .reply()
+            .map_err(unavailable_error)?;
+
+        Ok(Self {
+            conn,
+            window,
+            root,
+            atoms,
+        })
+    }
+
+    fn atom(&self, name: &str) -> Result<Atom> {
+        Ok(self
+            .conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(backend_error)?
+            .reply()
+            .map_err(backend_error)?
+            .atom)
+    }
+
+    fn atom_name(&self, atom: Atom) -> String {
+        self.conn
+            .get_atom_name(atom)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+            .unwrap_or_else(|| atom.to_string())
+    }
+
+    /// Read and delete `property` of the window, returning its type and value
+    fn take_property(&self, property: Atom) -> Result<(Atom, Vec<u8>)> {
+        let reply = self
+            .conn
+            .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX)
+            .map_err(backend_error)?
+            .reply()
+            .map_err(backend_error)?;
+
+        Ok((reply.type_, reply.value))
+    }
+}
+
+impl Owner {
+    /// Answer the requests for `selection` until the window is destroyed
+    fn serve(&self, selection: Atom) {
+        let mut transfers = Vec::new();
+
+        while let Ok(event) = self.x.conn.wait_for_event() {
+            // requestors that went away never ask for the rest of their transfer
+            let timeout = Duration::from_millis(constants::backend::SELECTION_TIMEOUT);
+            transfers.retain(|transfer: &Transfer| transfer.updated_at.elapsed() < timeout);
+
+            match event {
+                Event::DestroyNotify(event) if event.window == self.x.window => break,
+                Event::SelectionClear(event) if event.selection == selection => {
+                    self.targets.lock().clear();
+                }
+                Event::SelectionRequest(event) => {
+                    let _ = self.reply(event, &mut transfers);
+                }
+                Event::PropertyNotify(event) if event.state == Property::DELETE => {
+                    let _ = self.send_chunk(event, &mut transfers);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Largest property that fits in one request, in bytes
+    fn max_chunk(&self) -> usize {
+        self.x.conn.maximum_request_bytes() - CHANGE_PROPERTY_HEADER
+    }
+
+    fn reply(&self, event: SelectionRequestEvent, transfers: &mut Vec<Transfer>) -> Result<()> {
+        let x = &self.x;
+        let targets = self.targets.lock();
+
+        // obsolete clients leave the property unset
+        let property = match event.property {
+            NONE => event.target,
+            property => property,
+        };
+
+        let converted = if event.target == x.atoms.TARGETS {
+            let mut atoms = vec![x.atoms.TARGETS];
+            atoms.extend(targets.iter().map(|(target, _)| *target));
+
+            x.conn
+                .change_property32(
+                    PropMode::REPLACE,
+                    event.requestor,
+                    property,
+                    AtomEnum::ATOM,
+                    &atoms,
+                )
+                .map_err(backend_error)?;
+            true
+        } else if let Some((target, data)) =
+            targets.iter().find(|(target, _)| *target == event.target)
+        {
+            if data.len() > self.max_chunk() {
+                // the requestor deletes the INCR property once it is ready for the first chunk
+                x.conn
+                    .change_window_attributes(
+                        event.requestor,
+                        &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+                    )
+                    .map_err(backend_error)?;
+                x.conn
+                    .change_property32(
+                        PropMode::REPLACE,
+                        event.requestor,
+                        property,
+                        x.atoms.INCR,
+                        &[u32::try_from(data.len()).unwrap_or(u32::MAX)],
+                    )
+                    .map_err(backend_error)?;
+
+                transfers.retain(|transfer| {
+                    transfer.requestor != event.requestor || transfer.property != property
+                });
+                transfers.push(Transfer {
+                    requestor: event.requestor,
+                    property,
+                    target: *target,
+                    data: data.clone(),
+                    offset: 0,
+                    updated_at: Instant::now(),
+                });
+            } else {
+                x.conn
+                    .change_property8(PropMode::REPLACE, event.requestor, property, *target, data)
+                    .map_err(backend_error)?;
+            }
+            true
+        } else {
+            false
+        };
+
+        x.conn
+            .send_event(
+                false,
+                event.requestor,
+                EventMask::NO_EVENT,
+                SelectionNotifyEvent {
+                    response_type: SELECTION_NOTIFY_EVENT,
+                    sequence: 0,
+                    time: event.time,
+                    requestor: event.requestor,
+                    selection: event.selection,
+                    target: event.target,
+                    property: if converted { property } else { NONE },
+                },
+            )
+            .map_err(backend_error)?;
+        x.conn.flush().map_err(backend_error)?;
+
+        Ok(())
+    }
+
+    /// Send the next chunk of the transfer whose property the requestor deleted, ending with an
+    /// empty one
+    fn send_chunk(&self, event: PropertyNotifyEvent, transfers: &mut Vec<Transfer>) -> Result<()> {
+        let Some(index) = transfers.iter().position(|transfer| {
+            transfer.requestor == event.window && transfer.property == event.atom
+        }) else {
+            return Ok(());
+        };
+
+        let x = &self.x;
+        let transfer = &mut transfers[index];
+        let end = transfer.data.len().min(transfer.offset + self.max_chunk());
+
+        x.conn
+            .change_property8(
+                PropMode::REPLACE,
+                transfer.requestor,
+                transfer.property,
+                transfer.target,
+                &transfer.data[transfer.offset..end],
+            )
+            .map_err(backend_error)?;
+
+        if transfer.offset == end {
+            let transfer = transfers.remove(index);
+            x.conn
+                .change_window_attributes(
+                    transfer.requestor,
+                    &ChangeWindowAttributesAux::new().event_mask(EventMask::NO_EVENT),
+                )
+                .map_err(backend_error)?;
+        } else {
+            transfer.offset = end;
+            transfer.updated_at = Instant::now();
+        }
+
+        x.conn.flush().map_err(backend_error)?;
+
+        Ok(())
+    }
+}
+
+impl ClipboardBackend for SelectionBackend {
+    fn has(&self, format: ContentFormat) -> Result<bool> {
+        let candidates = self.candidates(&format)?;
+
+        Ok(self
+            .targets()?
+            .iter()
+            .any(|target| candidates.contains(target)))
+    }
+
+    fn get(&self, formats: &[ContentFormat]) -> Result<Vec<ClipboardContent>> {
+        let targets = self.targets()?;
+        let mut contents = Vec::new();
+
+        for format in formats {
+            let Some(target) = self
+                .candidates(format)?
+                .into_iter()
+                .find(|candidate| targets.contains(candidate))
+            else {
+                continue;
+            };
+
+            let data = match self.read(target) {
+                Ok(data) => data,
+                Err(Error::FormatUnavailable(_)) => continue,
+                Err(err) => return Err(err),
+            };
+
+            contents.push(match format {
+                ContentFormat::Text => {
+                    ClipboardContent::Text(String::from_utf8_lossy(&data).into())
+                }
+                ContentFormat::Rtf => ClipboardContent::Rtf(String::from_utf8_lossy(&data).into()),
+                ContentFormat::Html => {
+                    ClipboardContent::Html(String::from_utf8_lossy(&data).into())
+                }
+                ContentFormat::Image => ClipboardContent::Image(
+                    RustImageData::from_bytes(&data)
+                        .map_err(|err| Error::ImageDecode(err.to_string()))?,
+                ),
+                ContentFormat::Files => ClipboardContent::Files(
+                    String::from_utf8_lossy(&data)
+                        .lines()
+                        .filter(|line| line.starts_with(FILE_URI_PREFIX))
+                        .map(String::from)
+                        .collect(),
+                ),
+                ContentFormat::Other(name) => ClipboardContent::Other(name.clone(), data),
+            });
+        }
+
+        Ok(contents)
+    }
+
+    fn set(&self, contents: Vec<ClipboardContent>) -> Result<()> {
+        let mut targets = Vec::new();
+
+        {
+            let x = self.reader.lock();
+            let atoms = &x.atoms;
+
+            for content in contents {
+                match content {
+                    ClipboardContent::Text(text) => {
+                        for target in [
+                            atoms.UTF8_STRING,
+                            atoms.TEXT_PLAIN_UTF8,
+                            atoms.STRING,
+                            atoms.TEXT,
+                            atoms.TEXT_PLAIN,
+                        ] {
+                            targets.push((target, text.as_bytes().to_vec()));
+                        }
+                    }
+                    ClipboardContent::Rtf(rtf) => targets.push((atoms.RTF, rtf.into_bytes())),
+                    ClipboardContent::Html(html) => targets.push((atoms.HTML, html.into_bytes())),
+                    ClipboardContent::Image(image) => {
+                        let png = image
+                            .to_png()
+                            .map_err(|err| Error::ImageDecode(err.to_string()))?;
+                        targets.push((atoms.PNG, png.get_bytes().to_vec()));
+                    }
+                    ClipboardContent::Files(files) => {
+                        let uris: Vec<String> = files
+                            .iter()
+                            .map(|file| match file.starts_with(FILE_URI_PREFIX) {
+                                true => file.clone(),
+                                false => format!("{FILE_URI_PREFIX}{file}"),
+                            })
+                            .collect();
+                        let paths: Vec<&str> = files
+                            .iter()
+                            .map(|file| file.strip_prefix(FILE_URI_PREFIX).unwrap_or(file))
+                            .collect();
+
+                        targets.push((atoms.URI_LIST, uris.join("\r\n").into_bytes()));
+                        targets.push((
+                            atoms.GNOME_COPIED_FILES,
+                            format!("copy\n{}", uris.join("\n")).into_bytes(),
+                        ));
+                        targets.push((atoms.UTF8_STRING, paths.join("\n").into_bytes()));
+                    }
+                    ClipboardContent::Other(name, buffer) => targets.push((x.atom(&name)?, buffer)),
+                }
+            }
+        }
+
+        self.own(targets)
+    }
+
+    fn clear(&self) -> Result<()> {
+        let x = &self.owner.x;
+        self.owner.targets.lock().clear();
+
+        x.conn
+            .set_selection_owner(NONE, self.selection, CURRENT_TIME)
+            .map_err(backend_error)?
+            .check()
+            .map_err(backend_error)
+    }
+
+    fn available_formats(&self) -> Result<Vec<FormatInfo>> {
+        let targets = self.targets()?;
+        let x = self.reader.lock();
+
+        Ok(targets
+            .into_iter()
+            .map(|target| x.atom_name(target))
+            .filter(|name| !constants::backend::META_TARGETS.contains(&name.as_str()))
+            .map(|name| FormatInfo {
+                format: ClipboardFormat::from_native(&name),
+                size: None,
+                name,
+            })
+            .collect())
+    }
+
+    fn get_buffer(&self, name: &str) -> Result<Vec<u8>> {
+        let target = self.reader.lock().atom(name)?;
+
+        self.read(target)
+    }
+
+    fn watch(&self, mut handler: ChangeHandler) -> Result<WatchHandle> {
+        let x = XConnection::new()?;
+
+        xfixes::query_version(&x.conn, 5, 0)
+            .map_err(unavailable_error)?
+            .reply()
+            .map_err(unavailable_error)?;
+
+        xfixes::select_selection_input(
+            &x.conn,
+            x.root,
+            self.selection,
+            xfixes::SelectionEventMask::SET_SELECTION_OWNER
+                | xfixes::SelectionEventMask::SELECTION_CLIENT_CLOSE
+                | xfixes::SelectionEventMask::SELECTION_WINDOW_DESTROY,
+        )
+        .map_err(unavailable_error)?
+        .check()
+        .map_err(unavailable_error)?;
+
+        let (stop, stopped) = channel::<()>();
+        let interval = Duration::from_millis(constants::backend::SELECTION_POLL_INTERVAL);
+
+        spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                loop {
+                    match x.conn.poll_for_event() {
+                        Ok(Some(Event::XfixesSelectionNotify(_))) => handler(),
+                        Ok(Some(_)) => {}
+                        Ok(None) => break,
+                        Err(_) => return,
+                    }
+                }
+            }
+        });
+
+        Ok(WatchHandle::new(move || {
+            let _ = stop.send(());
+        }))
+    }
+}
+
+fn backend_error(err: impl Display) -> Error {
+    Error::Backend(err.to_string())
+}
+
+fn unavailable_error(err: impl Display) -> Error {
+    Error::BackendUnavailable(err.to_string())
+}
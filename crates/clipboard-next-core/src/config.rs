@@ -0,0 +1,306 @@
+use crate::constants;
+use crate::models::{ClipboardFormat, ClipboardSnapshot, Selection};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Clipboard policies applied by [`ClipboardNextManager`](crate::ClipboardNextManager)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    /// Directory images are saved to when no path is given, must be absolute
+    pub storage_dir: Option<PathBuf>,
+    /// Format of the images saved by `read_image`
+    pub image_format: ImageFormat,
+    /// Formats that can be read and written, every other format is ignored
+    pub formats: Vec<ClipboardFormat>,
+    /// Size limits for reads and writes
+    pub limits: Limits,
+    /// Clipboard changes closer together than this interval are reported once, in milliseconds
+    pub debounce_interval: u64,
+    /// Longest time a burst of changes is held back by the debounce interval, in milliseconds, `0` waits for the burst to end
+    pub debounce_max_wait: u64,
+    /// Drop changes caused by writes through the manager instead of reporting them with the `self` origin
+    pub suppress_echo: bool,
+    /// Time to wait for a clipboard operation before failing with a timeout, in milliseconds, `0` waits forever
+    pub timeout: u64,
+    /// Retry policy for transient clipboard failures
+    pub retry: RetryPolicy,
+    /// How clipboard changes are detected
+    pub watch_mode: WatchMode,
+    /// Interval between two checks of the polling watcher, in milliseconds
+    pub poll_interval: u64,
+    /// Number of recent changes kept for late subscribers, the latest change is always kept
+    pub backlog: usize,
+    /// Selections the watcher reports changes of, an empty list watches the clipboard
+    pub watch_selections: Vec<Selection>,
+}
+
+/// Format of the images saved to disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+/// How the watcher detects clipboard changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchMode {
+    /// Use the backend's change notifications, polling if the backend cannot watch
+    #[default]
+    Auto,
+    /// Only use the backend's change notifications
+    Native,
+    /// Compare the clipboard fingerprint every [`Config::poll_interval`]
+    Polling,
+}
+
+/// Retry policy for transient clipboard failures, e.g. a selection owner that changed mid-read
+///
+/// The delay doubles after every attempt, starting at `backoff` and capped at `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt, `0` disables retries
+    pub max_retries: u32,
+    /// Delay before the first retry, in milliseconds
+    pub backoff: u64,
+    /// Maximum delay between two retries, in milliseconds
+    pub max_backoff: u64,
+}
+
+/// Options of a single operation, unset fields fall back to the [`Config`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OperationOptions {
+    /// Time to wait for the operation, in milliseconds, `0` waits forever
+    pub timeout: Option<u64>,
+    /// Retry policy for transient failures
+    pub retry: Option<RetryPolicy>,
+    /// Drop the change caused by a write instead of reporting it with the `self` origin
+    pub suppress_echo: Option<bool>,
+    /// Selection to read or write, defaults to the clipboard
+    pub selection: Option<Selection>,
+}
+
+/// Contents captured by [`ClipboardNextManager::snapshot`](crate::ClipboardNextManager::snapshot)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnapshotOptions {
+    /// Formats whose content is captured, `None` captures every enabled format
+    pub formats: Option<Vec<ClipboardFormat>>,
+    /// Save the image to `image_dir`, otherwise only its size is captured
+    pub save_image: bool,
+    /// Directory the image is saved to, defaults to [`Config::storage_dir`]
+    pub image_dir: Option<PathBuf>,
+}
+
+/// Clipboard changes to report, a change is reported if it matches every field that is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChangeFilter {
+    /// Report changes that leave any of these formats on the clipboard
+    pub formats: Option<Vec<ClipboardFormat>>,
+    /// Minimum size of the captured text in bytes, changes without text are not affected
+    pub min_text_size: Option<u64>,
+    /// Maximum size of the captured text in bytes, changes without text are not affected
+    pub max_text_size: Option<u64>,
+}
+
+/// Size limits, `None` means unlimited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Limits {
+    /// Maximum size of text, rtf and html content in bytes
+    pub max_text_size: Option<u64>,
+    /// Maximum size of a decoded RGBA image in bytes
+    pub max_image_size: Option<u64>,
+    /// Maximum number of files
+    pub max_files: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            storage_dir: None,
+            image_format: ImageFormat::default(),
+            formats: ClipboardFormat::ALL.to_vec(),
+            limits: Limits::default(),
+            debounce_interval: 0,
+            debounce_max_wait: 0,
+            suppress_echo: false,
+            timeout: constants::worker::TIMEOUT,
+            retry: RetryPolicy::default(),
+            watch_mode: WatchMode::default(),
+            poll_interval: constants::watcher::POLL_INTERVAL,
+            backlog: constants::watcher::BACKLOG,
+            watch_selections: vec![Selection::Clipboard],
+        }
+    }
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            formats: None,
+            save_image: true,
+            image_dir: None,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: constants::worker::MAX_RETRIES,
+            backoff: constants::worker::BACKOFF,
+            max_backoff: constants::worker::MAX_BACKOFF,
+        }
+    }
+}
+
+impl ChangeFilter {
+    /// Check if a change that left `snapshot` on the clipboard is reported
+    pub fn matches(&self, snapshot: &ClipboardSnapshot) -> bool {
+        let formats = self.formats.as_ref().map_or(true, |formats| {
+            snapshot
+                .formats
+                .iter()
+                .any(|format| formats.contains(format))
+        });
+
+        let text_size = snapshot.text.as_ref().map_or(true, |text| {
+            let size = text.len() as u64;
+
+            self.min_text_size.map_or(true, |min| size >= min)
+                && self.max_text_size.map_or(true, |max| size <= max)
+        });
+
+        formats && text_size
+    }
+}
+
+impl RetryPolicy {
+    /// No retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the retry following `attempt`, starting at `0`
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX));
+
+        Duration::from_millis(delay.min(self.max_backoff))
+    }
+}
+
+impl Config {
+    /// Check if the format can be read and written
+    pub fn is_enabled(&self, format: ClipboardFormat) -> bool {
+        self.formats.contains(&format)
+    }
+
+    /// Check the values that cannot be used, e.g. a relative [`Config::storage_dir`]
+    pub fn validate(&self) -> Result<()> {
+        match &self.storage_dir {
+            Some(storage_dir) if !storage_dir.is_absolute() => {
+                Err(Error::InvalidPath(storage_dir.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ImageFormat {
+    /// File extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_max_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            backoff: 50,
+            max_backoff: 300,
+        };
+
+        assert_eq!(policy.delay(0), Duration::from_millis(50));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(300));
+        assert_eq!(policy.delay(100), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn change_filter_matches_formats_and_text_size() {
+        let snapshot = ClipboardSnapshot {
+            formats: vec![ClipboardFormat::Text, ClipboardFormat::Html],
+            text: Some("hello".into()),
+            ..ClipboardSnapshot::default()
+        };
+
+        assert!(ChangeFilter::default().matches(&snapshot));
+
+        let images = ChangeFilter {
+            formats: Some(vec![ClipboardFormat::Image]),
+            ..ChangeFilter::default()
+        };
+        assert!(!images.matches(&snapshot));
+
+        let html = ChangeFilter {
+            formats: Some(vec![ClipboardFormat::Image, ClipboardFormat::Html]),
+            ..ChangeFilter::default()
+        };
+        assert!(html.matches(&snapshot));
+
+        let long_text = ChangeFilter {
+            min_text_size: Some(6),
+            ..ChangeFilter::default()
+        };
+        assert!(!long_text.matches(&snapshot));
+
+        let short_text = ChangeFilter {
+            min_text_size: Some(5),
+            max_text_size: Some(5),
+            ..ChangeFilter::default()
+        };
+        assert!(short_text.matches(&snapshot));
+
+        // size limits only apply to changes with text
+        let image = ClipboardSnapshot {
+            formats: vec![ClipboardFormat::Image],
+            ..ClipboardSnapshot::default()
+        };
+        assert!(long_text.matches(&image));
+    }
+
+    #[test]
+    fn validate_rejects_a_relative_storage_dir() {
+        let mut config = Config {
+            storage_dir: Some(PathBuf::from("images")),
+            ..Config::default()
+        };
+        assert_eq!(config.validate().unwrap_err().code(), "invalidPath");
+
+        config.storage_dir = Some(std::env::temp_dir());
+        config.validate().unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+/// clipboard backend constants
+pub mod backend {
+    /// minimum interval between two connection attempts, in milliseconds
+    pub const RETRY_INTERVAL: u64 = 1000;
+
+    /// time for the native watcher to fail before it is considered running, in milliseconds
+    pub const WATCH_STARTUP: u64 = 200;
+
+    /// time for an X11 selection owner to answer a request, in milliseconds
+    pub const SELECTION_TIMEOUT: u64 = 500;
+
+    /// interval between two checks for X11 selection events, in milliseconds
+    pub const SELECTION_POLL_INTERVAL: u64 = 20;
+
+    /// X11 targets describing the selection rather than holding content
+    pub const META_TARGETS: [&str; 5] =
+        ["TARGETS", "TIMESTAMP", "MULTIPLE", "SAVE_TARGETS", "DELETE"];
+}
+
+/// clipboard worker constants
+pub mod worker {
+    /// default time to wait for a clipboard operation, in milliseconds
+    pub const TIMEOUT: u64 = 5000;
+
+    /// default number of retries after a transient failure
+    pub const MAX_RETRIES: u32 = 2;
+
+    /// default delay before the first retry, in milliseconds
+    pub const BACKOFF: u64 = 50;
+
+    /// default maximum delay between two retries, in milliseconds
+    pub const MAX_BACKOFF: u64 = 1000;
+}
+
+/// clipboard watcher constants
+pub mod watcher {
+    /// longest time between a write and the change it causes, in milliseconds
+    pub const SELF_WRITE_WINDOW: u64 = 1000;
+
+    /// default interval between two checks of the polling watcher, in milliseconds
+    pub const POLL_INTERVAL: u64 = 500;
+
+    /// default number of recent changes kept by the worker
+    pub const BACKLOG: usize = 1;
+}
+
+/// clipboard manager constants
+pub mod manager {
+    /// width and height of the thumbnail an image without raw bytes is fingerprinted by, in pixels
+    pub const FINGERPRINT_SAMPLE: u32 = 16;
+}
@@ -0,0 +1,123 @@
+use crate::models::Selection;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Result alias
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Clipboard error
+///
+/// Serialized to the frontend as `{ code, message, details }`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The clipboard does not contain the requested format
+    #[error("clipboard does not contain {0}")]
+    FormatUnavailable(String),
+
+    /// The format is not enabled in the [`Config`](crate::Config)
+    #[error("{0} is not enabled")]
+    FormatDisabled(String),
+
+    /// The content is larger than the configured limit
+    #[error("{format} content of {size} exceeds the limit of {limit}")]
+    SizeLimitExceeded {
+        format: String,
+        size: u64,
+        limit: u64,
+    },
+
+    /// The clipboard backend could not be reached
+    #[error("clipboard backend is unavailable: {0}")]
+    BackendUnavailable(String),
+
+    /// The clipboard backend rejected the operation
+    #[error("clipboard operation failed: {0}")]
+    Backend(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The clipboard or file image could not be decoded or encoded
+    #[error("failed to process image: {0}")]
+    ImageDecode(String),
+
+    #[error("invalid path: {}", .0.display())]
+    InvalidPath(PathBuf),
+
+    #[error("clipboard watcher is already running")]
+    WatcherAlreadyRunning,
+
+    #[error("clipboard watcher is not running")]
+    WatcherNotRunning,
+
+    /// The clipboard operation did not finish in time
+    #[error("clipboard operation timed out after {}ms", .0.as_millis())]
+    Timeout(Duration),
+
+    /// The clipboard operation was cancelled before it started
+    #[error("clipboard operation was cancelled")]
+    Cancelled,
+
+    /// The selection does not exist on this platform or backend
+    #[error("{} selection is not supported", .0.name())]
+    SelectionUnsupported(Selection),
+}
+
+impl Error {
+    /// Stable identifier of the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::FormatUnavailable(_) => "formatUnavailable",
+            Error::FormatDisabled(_) => "formatDisabled",
+            Error::SizeLimitExceeded { .. } => "sizeLimitExceeded",
+            Error::BackendUnavailable(_) => "backendUnavailable",
+            Error::Backend(_) => "backend",
+            Error::Io(_) => "io",
+            Error::ImageDecode(_) => "imageDecode",
+            Error::InvalidPath(_) => "invalidPath",
+            Error::WatcherAlreadyRunning => "watcherAlreadyRunning",
+            Error::WatcherNotRunning => "watcherNotRunning",
+            Error::Timeout(_) => "timeout",
+            Error::Cancelled => "cancelled",
+            Error::SelectionUnsupported(_) => "selectionUnsupported",
+        }
+    }
+
+    /// Check if the operation may succeed when retried
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Backend(_))
+    }
+
+    /// Additional machine-readable information, e.g. the missing format or the offending path
+    pub fn details(&self) -> Option<String> {
+        match self {
+            Error::FormatUnavailable(format) | Error::FormatDisabled(format) => {
+                Some(format.clone())
+            }
+            Error::SizeLimitExceeded { format, .. } => Some(format.clone()),
+            Error::BackendUnavailable(reason)
+            | Error::Backend(reason)
+            | Error::ImageDecode(reason) => Some(reason.clone()),
+            Error::Io(err) => Some(err.kind().to_string()),
+            Error::InvalidPath(path) => Some(path.display().to_string()),
+            Error::Timeout(timeout) => Some(timeout.as_millis().to_string()),
+            Error::SelectionUnsupported(selection) => Some(selection.name().to_string()),
+            Error::WatcherAlreadyRunning | Error::WatcherNotRunning | Error::Cancelled => None,
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
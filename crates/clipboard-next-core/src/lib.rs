@@ -0,0 +1,31 @@
+//! Tauri-independent clipboard operations used by `tauri-plugin-clipboard-next`.
+//!
+//! ```rust,ignore
+//! use clipboard_next_core::{ClipboardNextManager, SystemBackend};
+//!
+//! let clipboard = ClipboardNextManager::new(Box::new(SystemBackend::new()));
+//! clipboard.write_text("Hello, World!".to_string())?;
+//! let text = clipboard.read_text()?;
+//! ```
+
+mod backend;
+mod config;
+mod constants;
+mod error;
+mod manager;
+mod models;
+mod utils;
+mod watcher;
+mod worker;
+
+pub use backend::{ChangeHandler, ClipboardBackend, MemoryBackend, SystemBackend, WatchHandle};
+pub use clipboard_rs::{ClipboardContent, ContentFormat, RustImageData};
+pub use config::{
+    ChangeFilter, Config, ImageFormat, Limits, OperationOptions, RetryPolicy, SnapshotOptions,
+    WatchMode,
+};
+pub use error::*;
+pub use manager::ClipboardNextManager;
+pub use models::*;
+pub use watcher::{debounce, Merge, WatchEvent, WatchHandler};
+pub use worker::{CancelGuard, ClipboardWorker, Pending};
@@ -0,0 +1,920 @@
+use crate::backend::{ChangeHandler, ClipboardBackend, SystemBackend, WatchHandle};
+use crate::config::{Config, ImageFormat, SnapshotOptions, WatchMode};
+use crate::models::*;
+use crate::watcher::{self, FingerprintReader, SelfWrite, WatchHandler, WatchState};
+use crate::{constants, utils, Error, Result};
+use clipboard_rs::common::{ContentData, RustImage};
+use clipboard_rs::{ClipboardContent, ContentFormat, RustImageData};
+use parking_lot::Mutex;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Clipboard operations on top of a [`ClipboardBackend`]
+pub struct ClipboardNextManager {
+    backend: Arc<dyn ClipboardBackend>,
+    config: Config,
+    watch: Mutex<Option<Watch>>,
+    self_write: Arc<Mutex<Option<SelfWrite>>>,
+    suppress_echo: Mutex<Option<bool>>,
+    change_count: Arc<AtomicU64>,
+    last_fingerprint: Mutex<Option<String>>,
+}
+
+struct Watch {
+    handle: WatchHandle,
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl Default for ClipboardNextManager {
+    fn default() -> Self {
+        Self::new(Box::new(SystemBackend::new()))
+    }
+}
+
+impl ClipboardNextManager {
+    pub fn new(backend: Box<dyn ClipboardBackend>) -> Self {
+        Self::with_config(backend, Config::default())
+    }
+
+    pub fn with_config(backend: Box<dyn ClipboardBackend>, config: Config) -> Self {
+        Self {
+            backend: Arc::from(backend),
+            config,
+            watch: Mutex::default(),
+            self_write: Arc::default(),
+            suppress_echo: Mutex::default(),
+            change_count: Arc::default(),
+            last_fingerprint: Mutex::default(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Manager of another selection with the same config, see [`ClipboardBackend::selection`]
+    ///
+    /// It watches the selection and tracks its own writes independently of this manager.
+    pub fn for_selection(&self, selection: Selection) -> Result<Self> {
+        Ok(Self::with_config(
+            self.backend.selection(selection)?,
+            self.config.clone(),
+        ))
+    }
+
+    /// Check if the clipboard contains the given format, disabled formats are never reported
+    pub fn has(&self, format: ContentFormat) -> Result<bool> {
+        if self.check_enabled(&format).is_err() {
+            return Ok(false);
+        }
+
+        self.backend.has(format)
+    }
+
+    /// Get the content of the given format, failing with [`Error::FormatUnavailable`] if it is missing
+    pub fn get(&self, format: ContentFormat) -> Result<ClipboardContent> {
+        self.check_enabled(&format)?;
+
+        let content = self
+            .backend
+            .get(std::slice::from_ref(&format))?
+            .pop()
+            .ok_or_else(|| Error::FormatUnavailable(utils::format_name(&format)))?;
+
+        self.check_size(&content)?;
+
+        Ok(content)
+    }
+
+    /// Replace the clipboard contents, rejecting them all if any format is disabled
+    pub fn set(&self, contents: Vec<ClipboardContent>) -> Result<()> {
+        for content in &contents {
+            self.check_enabled(&content.get_format())?;
+            self.check_size(content)?;
+        }
+
+        self.write_backend(|backend| backend.set(contents))
+    }
+
+    /// Clear the clipboard, the change is reported even with [`Config::suppress_echo`] set
+    pub fn clear(&self) -> Result<()> {
+        // dropping it would hide from listeners that the clipboard is now empty
+        self.with_suppress_echo(Some(false), || {
+            self.write_backend(|backend| backend.clear())
+        })
+    }
+
+    pub fn status(&self) -> ClipboardStatus {
+        self.backend.status()
+    }
+
+    /// Call `handler` on every clipboard change until [`ClipboardNextManager::stop_watch`] is called
+    ///
+    /// Changes caused by writes through this manager are reported with [`ChangeOrigin::Internal`],
+    /// or not at all if [`Config::suppress_echo`] is set. Changes are detected as set by
+    /// [`Config::watch_mode`].
+    pub fn start_watch(&self, handler: WatchHandler) -> Result<()> {
+        let backend = self.backend.clone();
+        let config = self.config.clone();
+
+        self.start_watch_with(
+            handler,
+            Box::new(move || fingerprint(backend.as_ref(), &config)),
+        )
+    }
+
+    /// [`ClipboardNextManager::start_watch`] with the polling watcher reading the fingerprint
+    /// through `read_fingerprint`, e.g. queued on a [`ClipboardWorker`](crate::ClipboardWorker)
+    pub(crate) fn start_watch_with(
+        &self,
+        mut handler: WatchHandler,
+        read_fingerprint: FingerprintReader,
+    ) -> Result<()> {
+        let mut watch = self.watch.lock();
+
+        if watch.is_some() {
+            return Err(Error::WatcherAlreadyRunning);
+        }
+
+        let state = Arc::new(Mutex::new(WatchState::new()));
+
+        let report_state = state.clone();
+        let handler: WatchHandler = Box::new(move |event| {
+            if !event.is_suppressed() && report_state.lock().report() {
+                handler(event);
+            }
+        });
+
+        let handler = match self.config.debounce_interval {
+            0 => handler,
+            interval => watcher::debounce(
+                handler,
+                Duration::from_millis(interval),
+                match self.config.debounce_max_wait {
+                    0 => None,
+                    max_wait => Some(Duration::from_millis(max_wait)),
+                },
+            ),
+        };
+
+        let handler = Arc::new(Mutex::new(handler));
+        let poll_interval = Duration::from_millis(self.config.poll_interval);
+
+        let on_change = |polling: bool| -> ChangeHandler {
+            let handler = handler.clone();
+            let change_state = state.clone();
+            let change_count = self.change_count.clone();
+            let self_write = self.self_write.clone();
+
+            // a polled change is seen up to one interval after the write that caused it
+            let mut self_write_window =
+                Duration::from_millis(constants::watcher::SELF_WRITE_WINDOW);
+
+            if polling {
+                self_write_window += poll_interval;
+            }
+
+            Box::new(move || {
+                change_count.fetch_add(1, Ordering::Relaxed);
+                change_state.lock().change();
+                (handler.lock())(watcher::change_event(&self_write, self_write_window));
+            })
+        };
+
+        let poll =
+            |read_fingerprint| watcher::poll(read_fingerprint, poll_interval, on_change(true));
+
+        let (handle, polling) = match self.config.watch_mode {
+            WatchMode::Native => (self.backend.watch(on_change(false))?, false),
+            WatchMode::Polling => (poll(read_fingerprint), true),
+            WatchMode::Auto => match self.backend.watch(on_change(false)) {
+                Ok(handle) => (handle, false),
+                Err(_) => (poll(read_fingerprint), true),
+            },
+        };
+
+        state.lock().set_polling(polling);
+
+        *watch = Some(Watch { handle, state });
+
+        Ok(())
+    }
+
+    pub fn stop_watch(&self) -> Result<()> {
+        if let Some(watch) = self.watch.lock().take() {
+            watch.handle.stop();
+        }
+
+        Ok(())
+    }
+
+    /// Stop reporting changes without stopping the watcher, until `resume_after` has elapsed if given
+    pub fn pause_watch(&self, resume_after: Option<Duration>) -> Result<()> {
+        match self.watch.lock().as_ref() {
+            Some(watch) => {
+                watch.state.lock().pause(resume_after);
+                Ok(())
+            }
+            None => Err(Error::WatcherNotRunning),
+        }
+    }
+
+    pub fn resume_watch(&self) -> Result<()> {
+        match self.watch.lock().as_ref() {
+            Some(watch) => {
+                watch.state.lock().resume();
+                Ok(())
+            }
+            None => Err(Error::WatcherNotRunning),
+        }
+    }
+
+    pub fn watch_status(&self) -> WatchStatus {
+        match self.watch.lock().as_ref() {
+            Some(watch) => watch.state.lock().status(),
+            None => WatchStatus::default(),
+        }
+    }
+
+    pub fn read_text(&self) -> Result<String> {
+        match self.get(ContentFormat::Text)? {
+            ClipboardContent::Text(text) => Ok(text),
+            _ => Err(Error::FormatUnavailable("text".to_string())),
+        }
+    }
+
+    pub fn read_rtf(&self) -> Result<String> {
+        match self.get(ContentFormat::Rtf)? {
+            ClipboardContent::Rtf(rtf) => Ok(rtf),
+            _ => Err(Error::FormatUnavailable("rtf".to_string())),
+        }
+    }
+
+    pub fn read_html(&self) -> Result<String> {
+        match self.get(ContentFormat::Html)? {
+            ClipboardContent::Html(html) => Ok(html),
+            _ => Err(Error::FormatUnavailable("html".to_string())),
+        }
+    }
+
+    /// Read the clipboard image and save it, named after its content hash, in `file_path`
+    pub fn read_image(&self, file_path: PathBuf) -> Result<ReadImage> {
+        match self.get(ContentFormat::Image)? {
+            ClipboardContent::Image(image) => self.save_image(&image, &file_path),
+            _ => Err(Error::FormatUnavailable("image".to_string())),
+        }
+    }
+
+    pub fn read_files(&self) -> Result<ReadFiles> {
+        match self.get(ContentFormat::Files)? {
+            ClipboardContent::Files(files) => Ok(read_files(files)),
+            _ => Err(Error::FormatUnavailable("files".to_string())),
+        }
+    }
+
+    /// Check if the clipboard contains a custom format, e.g. a MIME type such as `image/svg+xml`
+    pub fn has_format(&self, format: &str) -> Result<bool> {
+        self.has(ContentFormat::Other(format.to_string()))
+    }
+
+    /// Read the raw bytes of a custom format
+    pub fn read_format(&self, format: &str) -> Result<Vec<u8>> {
+        match self.get(ContentFormat::Other(format.to_string()))? {
+            ClipboardContent::Other(_, buffer) => Ok(buffer),
+            _ => Err(Error::FormatUnavailable(format.to_string())),
+        }
+    }
+
+    /// List the formats the clipboard contains, disabled formats are left out
+    ///
+    /// With `sizes`, formats the backend does not report a size for are read to find it. Images are
+    /// never read: the clipboard owner may have to encode the image once for every format it offers.
+    pub fn available_formats(&self, sizes: bool) -> Result<Vec<FormatInfo>> {
+        let mut formats = self.backend.available_formats()?;
+        formats.retain(|info| {
+            info.format
+                .map_or(true, |format| self.config.is_enabled(format))
+        });
+
+        if sizes {
+            for info in formats.iter_mut() {
+                if info.size.is_none() && !info.is_image() {
+                    info.size = self
+                        .backend
+                        .get_buffer(&info.name)
+                        .ok()
+                        .map(|buffer| buffer.len() as u64);
+                }
+            }
+        }
+
+        Ok(formats)
+    }
+
+    /// Check if the clipboard holds no format at all, disabled formats included
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.backend.available_formats()?.is_empty())
+    }
+
+    /// Number of clipboard changes seen by this manager, it only ever increases
+    ///
+    /// The watcher counts every change, including paused and suppressed ones. Without a running
+    /// watcher the [`fingerprint`](ClipboardNextManager::fingerprint) is compared with the one of
+    /// the previous call instead, which reads every format.
+    pub fn change_count(&self) -> Result<u64> {
+        if self.watch.lock().is_none() {
+            let fingerprint = self.fingerprint()?;
+            let mut last_fingerprint = self.last_fingerprint.lock();
+
+            if last_fingerprint
+                .as_ref()
+                .is_some_and(|last_fingerprint| *last_fingerprint != fingerprint)
+            {
+                self.change_count.fetch_add(1, Ordering::Relaxed);
+            }
+
+            *last_fingerprint = Some(fingerprint);
+        }
+
+        Ok(self.change_count.load(Ordering::Relaxed))
+    }
+
+    /// Hash of the clipboard contents across every enabled format, including custom ones, equal
+    /// contents give the same hash, also across runs and Rust releases
+    pub fn fingerprint(&self) -> Result<String> {
+        fingerprint(self.backend.as_ref(), &self.config)
+    }
+
+    /// Capture the clipboard contents in a single read
+    ///
+    /// Contents that exceed the size limits are left out, their format is still reported.
+    pub fn snapshot(&self, options: &SnapshotOptions) -> Result<ClipboardSnapshot> {
+        let enabled: Vec<ClipboardFormat> = ClipboardFormat::ALL
+            .into_iter()
+            .filter(|format| self.config.is_enabled(*format))
+            .collect();
+
+        let requested: Vec<ClipboardFormat> = enabled
+            .iter()
+            .copied()
+            .filter(|format| {
+                options
+                    .formats
+                    .as_ref()
+                    .map_or(true, |formats| formats.contains(format))
+            })
+            .collect();
+
+        let content_formats: Vec<ContentFormat> = requested
+            .iter()
+            .map(|format| ContentFormat::from(*format))
+            .collect();
+
+        let contents = self.backend.get(&content_formats)?;
+
+        let mut snapshot = ClipboardSnapshot::default();
+
+        for format in enabled {
+            let available = if requested.contains(&format) {
+                contents.iter().any(|content| {
+                    ClipboardFormat::from_content_format(&content.get_format()) == Some(format)
+                })
+            } else {
+                self.backend.has(format.into())?
+            };
+
+            if available {
+                snapshot.formats.push(format);
+            }
+        }
+
+        let image_dir = options
+            .image_dir
+            .as_ref()
+            .or(self.config.storage_dir.as_ref());
+
+        for content in contents {
+            if self.check_size(&content).is_err() {
+                continue;
+            }
+
+            match content {
+                ClipboardContent::Text(text) => snapshot.text = Some(text),
+                ClipboardContent::Rtf(rtf) => snapshot.rtf = Some(rtf),
+                ClipboardContent::Html(html) => snapshot.html = Some(html),
+                ClipboardContent::Image(image) => {
+                    let (width, height) = image.get_size();
+
+                    // an image that cannot be saved is still reported, without a path
+                    let saved_image = match image_dir {
+                        Some(image_dir) if options.save_image => {
+                            self.save_image(&image, image_dir).ok()
+                        }
+                        _ => None,
+                    };
+
+                    snapshot.image = Some(ImageInfo {
+                        width,
+                        height,
+                        size: saved_image.as_ref().map(|saved_image| saved_image.size),
+                        path: saved_image.map(|saved_image| saved_image.path),
+                    });
+                }
+                ClipboardContent::Files(files) => snapshot.files = Some(read_files(files)),
+                ClipboardContent::Other(_, _) => {}
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    pub fn write_text(&self, content: String) -> Result<()> {
+        self.set(vec![ClipboardContent::Text(content)])
+    }
+
+    pub fn write_rtf(&self, content: String) -> Result<()> {
+        self.set(self.with_text_alternative(ClipboardContent::Rtf(content.clone()), content))
+    }
+
+    pub fn write_html(&self, content: String) -> Result<()> {
+        self.set(self.with_text_alternative(ClipboardContent::Html(content.clone()), content))
+    }
+
+    pub fn write_image(&self, image_path: String) -> Result<()> {
+        let image_data = RustImageData::from_path(&image_path)
+            .map_err(|err| Error::ImageDecode(err.to_string()))?;
+        self.set(vec![ClipboardContent::Image(image_data)])
+    }
+
+    pub fn write_files(&self, files_path: Vec<String>) -> Result<()> {
+        self.set(vec![ClipboardContent::Files(files_path)])
+    }
+
+    /// Replace the clipboard contents with the raw bytes of a custom format
+    pub fn write_format(&self, format: String, buffer: Vec<u8>) -> Result<()> {
+        self.set(vec![ClipboardContent::Other(format, buffer)])
+    }
+
+    /// Replace the clipboard contents with every representation at once
+    ///
+    /// Unlike [`write_html`](ClipboardNextManager::write_html) no plain text alternative is added,
+    /// include a [`Representation::Text`] for apps that only paste text.
+    pub fn write(&self, representations: Vec<Representation>) -> Result<()> {
+        let contents = representations
+            .into_iter()
+            .map(into_content)
+            .collect::<Result<Vec<_>>>()?;
+
+        self.set(contents)
+    }
+
+    /// Run `f` with [`Config::suppress_echo`] overridden, used for per-operation options
+    pub(crate) fn with_suppress_echo<T>(
+        &self,
+        suppress_echo: Option<bool>,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        *self.suppress_echo.lock() = suppress_echo;
+        let result = f();
+        *self.suppress_echo.lock() = None;
+        result
+    }
+
+    /// Run a write on the backend, attributing the change it causes to this manager
+    fn write_backend(&self, f: impl FnOnce(&dyn ClipboardBackend) -> Result<()>) -> Result<()> {
+        let suppress = self
+            .suppress_echo
+            .lock()
+            .unwrap_or(self.config.suppress_echo);
+
+        *self.self_write.lock() = Some(SelfWrite::new(suppress));
+
+        let result = f(self.backend.as_ref());
+
+        if result.is_err() {
+            self.self_write.lock().take();
+        }
+
+        result
+    }
+
+    /// Save the image, named after its content hash, in `file_path`
+    fn save_image(&self, image: &RustImageData, file_path: &Path) -> Result<ReadImage> {
+        if !file_path.exists() {
+            fs::create_dir_all(file_path)?;
+        }
+
+        let (width, height) = image.get_size();
+
+        let dynamic_image = image
+            .get_dynamic_image()
+            .map_err(|err| Error::ImageDecode(err.to_string()))?;
+
+        let bytes = dynamic_image.as_bytes();
+
+        let mut hasher = DefaultHasher::new();
+
+        bytes.hash(&mut hasher);
+
+        let hash = hasher.finish();
+
+        let image_format = self.config.image_format;
+
+        let full_file_path = file_path.join(format!("{}.{}", hash, image_format.extension()));
+
+        if !full_file_path.exists() {
+            match full_file_path.to_str() {
+                None => {
+                    return Err(Error::InvalidPath(full_file_path));
+                }
+                Some(path_str) => {
+                    let buffer = match image_format {
+                        ImageFormat::Png => image.to_png(),
+                        ImageFormat::Jpeg => image.to_jpeg(),
+                    }
+                    .map_err(|err| Error::ImageDecode(err.to_string()))?;
+
+                    fs::write(path_str, buffer.get_bytes())?;
+                }
+            }
+        }
+
+        let file_size = utils::get_file_size(&full_file_path).unwrap_or(0);
+
+        Ok(ReadImage {
+            path: full_file_path,
+            width,
+            height,
+            size: file_size,
+        })
+    }
+
+    /// `content` with a plain text alternative, left out when text is disabled
+    fn with_text_alternative(
+        &self,
+        content: ClipboardContent,
+        text: String,
+    ) -> Vec<ClipboardContent> {
+        if self.config.is_enabled(ClipboardFormat::Text) {
+            vec![content, ClipboardContent::Text(text)]
+        } else {
+            vec![content]
+        }
+    }
+
+    /// Custom formats standing for a built-in format, e.g. `text/html`, follow that format
+    fn check_enabled(&self, format: &ContentFormat) -> Result<()> {
+        let format = match format {
+            ContentFormat::Other(name) => ClipboardFormat::from_native(name),
+            format => ClipboardFormat::from_content_format(format),
+        };
+
+        match format {
+            Some(format) if !self.config.is_enabled(format) => {
+                Err(Error::FormatDisabled(format.name().to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_size(&self, content: &ClipboardContent) -> Result<()> {
+        let limits = &self.config.limits;
+
+        let (size, limit) = match content {
+            ClipboardContent::Text(text)
+            | ClipboardContent::Rtf(text)
+            | ClipboardContent::Html(text) => (text.len() as u64, limits.max_text_size),
+            ClipboardContent::Image(image) => {
+                let (width, height) = image.get_size();
+                (width as u64 * height as u64 * 4, limits.max_image_size)
+            }
+            ClipboardContent::Files(files) => (files.len() as u64, limits.max_files),
+            ClipboardContent::Other(name, buffer) => match ClipboardFormat::from_native(name) {
+                Some(ClipboardFormat::Text | ClipboardFormat::Rtf | ClipboardFormat::Html) => {
+                    (buffer.len() as u64, limits.max_text_size)
+                }
+                // the encoded image is never larger than the decoded one the limit is meant for
+                Some(ClipboardFormat::Image) => (buffer.len() as u64, limits.max_image_size),
+                Some(ClipboardFormat::Files) => {
+                    let files = String::from_utf8_lossy(buffer)
+                        .lines()
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .count();
+
+                    (files as u64, limits.max_files)
+                }
+                None => return Ok(()),
+            },
+        };
+
+        match limit {
+            Some(limit) if size > limit => Err(Error::SizeLimitExceeded {
+                format: utils::format_name(&content.get_format()),
+                size,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn into_content(representation: Representation) -> Result<ClipboardContent> {
+    Ok(match representation {
+        Representation::Text { content } => ClipboardContent::Text(content),
+        Representation::Rtf { content } => ClipboardContent::Rtf(content),
+        Representation::Html { content } => ClipboardContent::Html(content),
+        Representation::Image { path, bytes } => {
+            let image = match (bytes, path) {
+                (Some(bytes), _) => RustImageData::from_bytes(&bytes),
+                (None, Some(path)) => RustImageData::from_path(&path),
+                (None, None) => {
+                    return Err(Error::ImageDecode(
+                        "image has neither a path nor bytes".to_string(),
+                    ))
+                }
+            };
+
+            ClipboardContent::Image(image.map_err(|err| Error::ImageDecode(err.to_string()))?)
+        }
+        Representation::Files { paths } => ClipboardContent::Files(paths),
+        Representation::Custom { format, bytes } => ClipboardContent::Other(format, bytes),
+    })
+}
+
+fn read_files(files: Vec<String>) -> ReadFiles {
+    let file_items: Vec<FileItem> = files
+        .into_iter()
+        .map(|path| {
+            let size = utils::get_file_size(&path).unwrap_or(0);
+            FileItem { path, size }
+        })
+        .collect();
+
+    let total_size = file_items.iter().map(|item| item.size).sum();
+
+    ReadFiles {
+        files: file_items,
+        size: total_size,
+    }
+}
+
+/// Hash of every format the clipboard contains, equal contents give the same hash
+///
+/// Every enabled format is hashed by name and content. Custom formats are hashed with their raw
+/// bytes, except image encodings: the image is hashed once, with the raw bytes of the first image
+/// format that can be read, so that the owner does not have to convert it into every encoding.
+/// The hash is stable, see [`utils::StableHasher`].
+pub(crate) fn fingerprint(backend: &dyn ClipboardBackend, config: &Config) -> Result<String> {
+    let mut hasher = utils::StableHasher::new();
+
+    let formats: Vec<FormatInfo> = backend
+        .available_formats()?
+        .into_iter()
+        .filter(|info| info.format.map_or(true, |format| config.is_enabled(format)))
+        .collect();
+
+    for info in &formats {
+        hasher.field(info.name.as_bytes());
+
+        if info.format.is_none() && !info.is_image() {
+            // a format the owner cannot convert is only hashed by name
+            if let Ok(buffer) = backend.get_buffer(&info.name) {
+                hasher.field(&buffer);
+            }
+        }
+    }
+
+    let builtin: Vec<ContentFormat> = [
+        ClipboardFormat::Text,
+        ClipboardFormat::Rtf,
+        ClipboardFormat::Html,
+        ClipboardFormat::Files,
+    ]
+    .into_iter()
+    .filter(|format| config.is_enabled(*format))
+    .map(ContentFormat::from)
+    .collect();
+
+    for content in backend.get(&builtin)? {
+        hasher.field(utils::format_name(&content.get_format()).as_bytes());
+
+        match &content {
+            ClipboardContent::Text(text)
+            | ClipboardContent::Rtf(text)
+            | ClipboardContent::Html(text) => hasher.field(text.as_bytes()),
+            ClipboardContent::Files(files) => {
+                for file in files {
+                    hasher.field(file.as_bytes());
+                }
+            }
+            ClipboardContent::Image(_) | ClipboardContent::Other(_, _) => {}
+        }
+    }
+
+    if config.is_enabled(ClipboardFormat::Image) {
+        let image = formats
+            .iter()
+            .filter(|info| info.format == Some(ClipboardFormat::Image))
+            .find_map(|info| backend.get_buffer(&info.name).ok());
+
+        match image {
+            Some(buffer) => hasher.field(&buffer),
+            // e.g. a Windows bitmap, which has no name to read it by: its size and a thumbnail
+            // stand for it instead of every pixel
+            None => {
+                if let Some(ClipboardContent::Image(image)) =
+                    backend.get(&[ContentFormat::Image])?.pop()
+                {
+                    let (width, height) = image.get_size();
+                    let sample = constants::manager::FINGERPRINT_SAMPLE;
+
+                    hasher.field(&width.to_le_bytes());
+                    hasher.field(&height.to_le_bytes());
+                    hasher.field(
+                        image
+                            .thumbnail(sample, sample)
+                            .and_then(|thumbnail| thumbnail.get_dynamic_image())
+                            .map_err(|err| Error::ImageDecode(err.to_string()))?
+                            .as_bytes(),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Limits;
+    use crate::MemoryBackend;
+    use std::env;
+
+    /// A 1x1 transparent PNG
+    const PNG: [u8; 67] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f,
+        0x15, 0xc4, 0x89, 0x00, 0x00, 0x00, 0x0a, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x00,
+        0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0d, 0x0a, 0x2d, 0xb4, 0x00, 0x00, 0x00, 0x00, 0x49,
+        0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    fn image() -> ClipboardContent {
+        ClipboardContent::Image(RustImageData::from_bytes(&PNG).unwrap())
+    }
+
+    /// An empty directory for this test run
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("clipboard-next-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn manager(formats: Vec<ClipboardFormat>) -> (ClipboardNextManager, MemoryBackend) {
+        let backend = MemoryBackend::new();
+        let config = Config {
+            formats,
+            ..Config::default()
+        };
+
+        (
+            ClipboardNextManager::with_config(Box::new(backend.clone()), config),
+            backend,
+        )
+    }
+
+    #[test]
+    fn read_image_saves_the_image_named_after_its_content() {
+        let backend = MemoryBackend::new();
+        let manager = ClipboardNextManager::new(Box::new(backend.clone()));
+        let dir = temp_dir("images");
+
+        let err = manager.read_image(dir.clone()).unwrap_err();
+        assert_eq!(err.code(), "formatUnavailable");
+
+        backend.set_external(vec![image()]);
+        let first = manager.read_image(dir.clone()).unwrap();
+        assert_eq!((first.width, first.height), (1, 1));
+        assert_eq!(first.path.parent(), Some(dir.as_path()));
+        assert_eq!(first.path.extension().unwrap(), "png");
+        assert_eq!(first.size, fs::metadata(&first.path).unwrap().len());
+
+        let second = manager.read_image(dir.clone()).unwrap();
+        assert_eq!(second.path, first.path);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_files_reports_the_size_of_every_file() {
+        let backend = MemoryBackend::new();
+        let manager = ClipboardNextManager::new(Box::new(backend.clone()));
+        let dir = temp_dir("files");
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("a.txt").to_string_lossy().into_owned();
+        let missing = dir.join("missing.txt").to_string_lossy().into_owned();
+        fs::write(&file, "hello").unwrap();
+
+        backend.set_external(vec![ClipboardContent::Files(vec![
+            file.clone(),
+            missing.clone(),
+        ])]);
+        let files = manager.read_files().unwrap();
+        assert_eq!(
+            files.files,
+            [
+                FileItem {
+                    path: file,
+                    size: 5
+                },
+                FileItem {
+                    path: missing,
+                    size: 0
+                },
+            ]
+        );
+        assert_eq!(files.size, 5);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn limits_reject_reads_and_writes_over_the_limit() {
+        let backend = MemoryBackend::new();
+        let config = Config {
+            limits: Limits {
+                max_text_size: Some(3),
+                max_image_size: Some(3),
+                max_files: Some(1),
+            },
+            ..Config::default()
+        };
+        let manager = ClipboardNextManager::with_config(Box::new(backend.clone()), config);
+
+        manager.write_text("abc".into()).unwrap();
+        let err = manager.write_text("abcd".into()).unwrap_err();
+        assert_eq!(err.code(), "sizeLimitExceeded");
+        assert_eq!(manager.read_text().unwrap(), "abc");
+
+        backend.set_external(vec![ClipboardContent::Text("abcd".into())]);
+        let err = manager.read_text().unwrap_err();
+        assert_eq!(err.code(), "sizeLimitExceeded");
+
+        let err = manager
+            .write_files(vec!["a".into(), "b".into()])
+            .unwrap_err();
+        assert_eq!(err.code(), "sizeLimitExceeded");
+
+        // a 1x1 image decodes to 4 bytes of RGBA
+        backend.set_external(vec![image()]);
+        let err = manager.read_image(temp_dir("limits")).unwrap_err();
+        assert_eq!(err.code(), "sizeLimitExceeded");
+    }
+
+    #[test]
+    fn set_rejects_writes_with_a_disabled_format() {
+        let (manager, backend) = manager(vec![ClipboardFormat::Text]);
+        backend.set_external(vec![ClipboardContent::Text("before".into())]);
+
+        let err = manager
+            .set(vec![
+                ClipboardContent::Text("after".into()),
+                ClipboardContent::Html("<b>after</b>".into()),
+            ])
+            .unwrap_err();
+
+        assert_eq!(err.code(), "formatDisabled");
+        assert_eq!(manager.read_text().unwrap(), "before");
+    }
+
+    #[test]
+    fn write_html_leaves_out_a_disabled_text_alternative() {
+        let (manager, backend) = manager(vec![ClipboardFormat::Html]);
+
+        manager.write_html("<b>html</b>".into()).unwrap();
+
+        assert_eq!(backend.contents().len(), 1);
+        assert_eq!(manager.read_html().unwrap(), "<b>html</b>");
+    }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let backend = MemoryBackend::new();
+        let manager = ClipboardNextManager::new(Box::new(backend.clone()));
+        backend.set_external(vec![ClipboardContent::Text("a".into())]);
+
+        // the same in every run and Rust release
+        assert_eq!(manager.fingerprint().unwrap(), "9750c8a4e61aa73f");
+
+        backend.set_external(vec![ClipboardContent::Text("b".into())]);
+        assert_ne!(manager.fingerprint().unwrap(), "9750c8a4e61aa73f");
+    }
+}
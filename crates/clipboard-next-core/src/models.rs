@@ -0,0 +1,473 @@
+use clipboard_rs::ContentFormat;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Clipboard formats with dedicated support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardFormat {
+    Text,
+    Rtf,
+    Html,
+    Image,
+    Files,
+}
+
+impl ClipboardFormat {
+    pub const ALL: [ClipboardFormat; 5] = [
+        ClipboardFormat::Text,
+        ClipboardFormat::Rtf,
+        ClipboardFormat::Html,
+        ClipboardFormat::Image,
+        ClipboardFormat::Files,
+    ];
+
+    /// Name of the format, as used by the frontend
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClipboardFormat::Text => "text",
+            ClipboardFormat::Rtf => "rtf",
+            ClipboardFormat::Html => "html",
+            ClipboardFormat::Image => "image",
+            ClipboardFormat::Files => "files",
+        }
+    }
+
+    /// Get the format of a [`ContentFormat`], `None` for custom formats
+    pub fn from_content_format(format: &ContentFormat) -> Option<Self> {
+        match format {
+            ContentFormat::Text => Some(ClipboardFormat::Text),
+            ContentFormat::Rtf => Some(ClipboardFormat::Rtf),
+            ContentFormat::Html => Some(ClipboardFormat::Html),
+            ContentFormat::Image => Some(ClipboardFormat::Image),
+            ContentFormat::Files => Some(ClipboardFormat::Files),
+            ContentFormat::Other(_) => None,
+        }
+    }
+
+    /// Get the format a platform's own format name stands for, e.g. a MIME type, a macOS UTI or a
+    /// Windows clipboard format name
+    pub fn from_native(name: &str) -> Option<Self> {
+        match name {
+            "UTF8_STRING"
+            | "STRING"
+            | "TEXT"
+            | "text/plain"
+            | "text/plain;charset=utf-8"
+            | "public.utf8-plain-text"
+            | "CF_UNICODETEXT"
+            | "CF_TEXT" => Some(ClipboardFormat::Text),
+            "text/rtf" | "application/rtf" | "public.rtf" | "Rich Text Format" => {
+                Some(ClipboardFormat::Rtf)
+            }
+            "text/html" | "public.html" | "HTML Format" => Some(ClipboardFormat::Html),
+            "image/png" | "public.png" | "public.tiff" | "PNG" | "CF_DIB" | "CF_DIBV5" => {
+                Some(ClipboardFormat::Image)
+            }
+            "text/uri-list" | "public.file-url" | "CF_HDROP" => Some(ClipboardFormat::Files),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClipboardFormat> for ContentFormat {
+    fn from(format: ClipboardFormat) -> Self {
+        match format {
+            ClipboardFormat::Text => ContentFormat::Text,
+            ClipboardFormat::Rtf => ContentFormat::Rtf,
+            ClipboardFormat::Html => ContentFormat::Html,
+            ClipboardFormat::Image => ContentFormat::Image,
+            ClipboardFormat::Files => ContentFormat::Files,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadImage {
+    pub path: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileItem {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadFiles {
+    pub files: Vec<FileItem>,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardStatus {
+    pub available: bool,
+    pub error: Option<String>,
+}
+
+/// One representation of the content written by [`ClipboardNextManager::write`](crate::ClipboardNextManager::write)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Representation {
+    Text {
+        content: String,
+    },
+    Rtf {
+        content: String,
+    },
+    Html {
+        content: String,
+    },
+    /// Encoded image, e.g. PNG, given either as bytes or as the path of an image file
+    Image {
+        path: Option<String>,
+        bytes: Option<Vec<u8>>,
+    },
+    Files {
+        paths: Vec<String>,
+    },
+    /// Raw bytes of a custom format, e.g. a MIME type
+    Custom {
+        format: String,
+        bytes: Vec<u8>,
+    },
+}
+
+/// Format advertised by the clipboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatInfo {
+    /// Name of the format as reported by the platform, e.g. a MIME type
+    pub name: String,
+    /// Format with dedicated support the name stands for, `None` for custom formats
+    pub format: Option<ClipboardFormat>,
+    /// Size of the content in bytes, `None` if the platform cannot report it
+    pub size: Option<u64>,
+}
+
+impl FormatInfo {
+    /// Check if the format holds an image, whatever its encoding
+    pub fn is_image(&self) -> bool {
+        self.format == Some(ClipboardFormat::Image)
+            || self.name.starts_with("image/")
+            || matches!(
+                self.name.as_str(),
+                "public.jpeg" | "public.heic" | "com.microsoft.bmp" | "CF_BITMAP" | "JFIF" | "GIF"
+            )
+    }
+}
+
+/// Metadata of the clipboard image
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Path the image was saved to, `None` if it was not saved
+    pub path: Option<PathBuf>,
+    /// Size of the saved image file in bytes
+    pub size: Option<u64>,
+}
+
+/// Clipboard contents captured in a single read
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardSnapshot {
+    /// Formats the clipboard contains, including formats whose content was not captured
+    pub formats: Vec<ClipboardFormat>,
+    pub text: Option<String>,
+    pub rtf: Option<String>,
+    pub html: Option<String>,
+    pub image: Option<ImageInfo>,
+    pub files: Option<ReadFiles>,
+}
+
+impl ClipboardSnapshot {
+    /// Keep only the given formats and their content
+    pub fn retain_formats(&mut self, formats: &[ClipboardFormat]) {
+        self.formats.retain(|format| formats.contains(format));
+
+        for format in ClipboardFormat::ALL {
+            if !formats.contains(&format) {
+                self.remove_content(format);
+            }
+        }
+    }
+
+    /// Drop the captured content, keeping the list of formats
+    pub fn clear_content(&mut self) {
+        for format in ClipboardFormat::ALL {
+            self.remove_content(format);
+        }
+    }
+
+    /// Check if both snapshots captured the same content for `format`
+    ///
+    /// Images are compared by the file they were saved to, or by their dimensions if they were not saved.
+    fn same_content(&self, other: &ClipboardSnapshot, format: ClipboardFormat) -> bool {
+        match format {
+            ClipboardFormat::Text => self.text == other.text,
+            ClipboardFormat::Rtf => self.rtf == other.rtf,
+            ClipboardFormat::Html => self.html == other.html,
+            ClipboardFormat::Image => self.image == other.image,
+            ClipboardFormat::Files => self.files == other.files,
+        }
+    }
+
+    fn remove_content(&mut self, format: ClipboardFormat) {
+        match format {
+            ClipboardFormat::Text => self.text = None,
+            ClipboardFormat::Rtf => self.rtf = None,
+            ClipboardFormat::Html => self.html = None,
+            ClipboardFormat::Image => self.image = None,
+            ClipboardFormat::Files => self.files = None,
+        }
+    }
+}
+
+/// Formats that differ between the clipboard contents before and after a change
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDelta {
+    /// Formats the clipboard did not contain before
+    pub added: Vec<ClipboardFormat>,
+    /// Formats the clipboard no longer contains
+    pub removed: Vec<ClipboardFormat>,
+    /// Formats whose captured content changed, formats whose content is not captured never change
+    pub changed: Vec<ClipboardFormat>,
+}
+
+impl ChangeDelta {
+    /// Compare two snapshots, every format of `current` is added if there is no `previous` one
+    pub fn between(previous: Option<&ClipboardSnapshot>, current: &ClipboardSnapshot) -> Self {
+        let mut delta = ChangeDelta::default();
+        let empty = ClipboardSnapshot::default();
+        let previous = previous.unwrap_or(&empty);
+
+        for format in ClipboardFormat::ALL {
+            match (
+                previous.formats.contains(&format),
+                current.formats.contains(&format),
+            ) {
+                (false, true) => delta.added.push(format),
+                (true, false) => delta.removed.push(format),
+                (true, true) if !previous.same_content(current, format) => {
+                    delta.changed.push(format)
+                }
+                _ => {}
+            }
+        }
+
+        delta
+    }
+
+    /// Keep only the given formats
+    pub fn retain_formats(&mut self, formats: &[ClipboardFormat]) {
+        self.added.retain(|format| formats.contains(format));
+        self.removed.retain(|format| formats.contains(format));
+        self.changed.retain(|format| formats.contains(format));
+    }
+
+    /// Fold the delta of a later change into this one, describing both changes at once
+    pub fn merge(&mut self, other: ChangeDelta) {
+        let mut delta = ChangeDelta::default();
+
+        for format in ClipboardFormat::ALL {
+            let state = |delta: &ChangeDelta| {
+                if delta.added.contains(&format) {
+                    Some(DeltaKind::Added)
+                } else if delta.removed.contains(&format) {
+                    Some(DeltaKind::Removed)
+                } else if delta.changed.contains(&format) {
+                    Some(DeltaKind::Changed)
+                } else {
+                    None
+                }
+            };
+
+            let merged = match (state(self), state(&other)) {
+                (None, kind) | (kind, None) => kind,
+                (Some(DeltaKind::Added), Some(DeltaKind::Removed)) => None,
+                (Some(DeltaKind::Added), _) => Some(DeltaKind::Added),
+                (Some(DeltaKind::Removed), Some(DeltaKind::Added)) => Some(DeltaKind::Changed),
+                (_, Some(DeltaKind::Removed)) => Some(DeltaKind::Removed),
+                _ => Some(DeltaKind::Changed),
+            };
+
+            match merged {
+                Some(DeltaKind::Added) => delta.added.push(format),
+                Some(DeltaKind::Removed) => delta.removed.push(format),
+                Some(DeltaKind::Changed) => delta.changed.push(format),
+                None => {}
+            }
+        }
+
+        *self = delta;
+    }
+}
+
+#[derive(Clone, Copy)]
+enum DeltaKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Where a clipboard change came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOrigin {
+    /// Written through this clipboard manager
+    #[serde(rename = "self")]
+    Internal,
+    /// Written by another application
+    External,
+}
+
+/// X11 selection an operation applies to
+///
+/// Only Linux has selections other than the clipboard, elsewhere they fail with
+/// [`Error::SelectionUnsupported`](crate::Error::SelectionUnsupported).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Selection {
+    /// The `CLIPBOARD` selection, copy and paste
+    #[default]
+    Clipboard,
+    /// The `PRIMARY` selection, the selected text pasted with a middle click
+    Primary,
+    /// The `SECONDARY` selection, rarely used
+    Secondary,
+}
+
+impl Selection {
+    /// Name of the selection atom
+    pub fn name(&self) -> &'static str {
+        match self {
+            Selection::Clipboard => "CLIPBOARD",
+            Selection::Primary => "PRIMARY",
+            Selection::Secondary => "SECONDARY",
+        }
+    }
+}
+
+/// Clipboard change reported by [`ClipboardWorker::start_watch`](crate::ClipboardWorker::start_watch)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardChange {
+    /// Number of clipboard changes merged into this one by the debounce interval, `0` for the
+    /// contents kept when the watcher started
+    pub merged: u64,
+    /// `self` if every merged change was written through this clipboard manager
+    pub origin: ChangeOrigin,
+    /// Selection that changed
+    pub selection: Selection,
+    /// Whether the clipboard holds no format at all after the change, including disabled and custom ones
+    pub cleared: bool,
+    /// Formats that appeared, disappeared or changed since the previous change
+    pub delta: ChangeDelta,
+    /// Clipboard contents after the last merged change
+    #[serde(flatten)]
+    pub snapshot: ClipboardSnapshot,
+}
+
+impl ClipboardChange {
+    /// Whether the clipboard is empty after the change, e.g. after a clear
+    pub fn is_cleared(&self) -> bool {
+        self.cleared
+    }
+}
+
+/// State of the clipboard watcher, times are milliseconds since the Unix epoch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatus {
+    pub running: bool,
+    /// Changes are detected by polling, because the backend cannot watch or [`WatchMode::Polling`](crate::WatchMode::Polling) is set
+    pub polling: bool,
+    /// Changes are not reported while paused
+    pub paused: bool,
+    pub started_at: Option<u64>,
+    /// Number of changes reported since the watcher started
+    pub events: u64,
+    /// Time of the last change, including changes made while paused
+    pub last_change_at: Option<u64>,
+    /// Time the watcher resumes on its own
+    pub resume_at: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(
+        added: &[ClipboardFormat],
+        removed: &[ClipboardFormat],
+        changed: &[ClipboardFormat],
+    ) -> ChangeDelta {
+        ChangeDelta {
+            added: added.to_vec(),
+            removed: removed.to_vec(),
+            changed: changed.to_vec(),
+        }
+    }
+
+    #[test]
+    fn delta_between_snapshots() {
+        let previous = ClipboardSnapshot {
+            formats: vec![ClipboardFormat::Text, ClipboardFormat::Rtf],
+            text: Some("a".into()),
+            ..ClipboardSnapshot::default()
+        };
+        let current = ClipboardSnapshot {
+            formats: vec![ClipboardFormat::Text, ClipboardFormat::Html],
+            text: Some("b".into()),
+            ..ClipboardSnapshot::default()
+        };
+
+        assert_eq!(
+            ChangeDelta::between(Some(&previous), &current),
+            delta(
+                &[ClipboardFormat::Html],
+                &[ClipboardFormat::Rtf],
+                &[ClipboardFormat::Text]
+            )
+        );
+        assert_eq!(
+            ChangeDelta::between(None, &current),
+            delta(&[ClipboardFormat::Text, ClipboardFormat::Html], &[], &[])
+        );
+    }
+
+    #[test]
+    fn delta_merge_describes_both_changes() {
+        let mut merged = delta(
+            &[ClipboardFormat::Text, ClipboardFormat::Html],
+            &[ClipboardFormat::Rtf, ClipboardFormat::Files],
+            &[ClipboardFormat::Image],
+        );
+
+        merged.merge(delta(
+            &[ClipboardFormat::Rtf],
+            &[ClipboardFormat::Html, ClipboardFormat::Image],
+            &[ClipboardFormat::Text],
+        ));
+
+        // added then removed cancels out, removed then added is a change
+        assert_eq!(
+            merged,
+            delta(
+                &[ClipboardFormat::Text],
+                &[ClipboardFormat::Image, ClipboardFormat::Files],
+                &[ClipboardFormat::Rtf]
+            )
+        );
+    }
+}
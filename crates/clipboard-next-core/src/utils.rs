@@ -0,0 +1,49 @@
+use clipboard_rs::ContentFormat;
+use std::path::Path;
+
+/// Get file size
+pub fn get_file_size<P: AsRef<Path>>(path: P) -> crate::Result<u64> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(metadata.len())
+}
+
+/// Get the name of a clipboard format
+pub fn format_name(format: &ContentFormat) -> String {
+    match format {
+        ContentFormat::Text => "text".to_string(),
+        ContentFormat::Rtf => "rtf".to_string(),
+        ContentFormat::Html => "html".to_string(),
+        ContentFormat::Image => "image".to_string(),
+        ContentFormat::Files => "files".to_string(),
+        ContentFormat::Other(name) => name.clone(),
+    }
+}
+
+/// 64-bit FNV-1a hash
+///
+/// Unlike [`DefaultHasher`](std::hash::DefaultHasher) its output is the same across runs,
+/// platforms and Rust releases, so hashes can be kept and compared later.
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    /// Add a field, prefixed by its length so that consecutive fields cannot run into each other
+    pub fn field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
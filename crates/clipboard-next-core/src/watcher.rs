@@ -0,0 +1,344 @@
+use crate::backend::{ChangeHandler, WatchHandle};
+use crate::models::{ChangeOrigin, ClipboardChange, WatchStatus};
+use crate::Result;
+use parking_lot::Mutex;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread::spawn;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Called with every reported clipboard change
+pub type WatchHandler = Box<dyn FnMut(WatchEvent) + Send + 'static>;
+
+/// Reads the clipboard fingerprint for the polling watcher
+pub(crate) type FingerprintReader = Box<dyn FnMut() -> Result<String> + Send + 'static>;
+
+/// Clipboard changes merged into one report
+#[derive(Debug, Clone, Copy)]
+pub struct WatchEvent {
+    /// Number of merged changes, `1` without a debounce interval
+    pub merged: u64,
+    /// [`ChangeOrigin::External`] if any of the merged changes came from another application
+    pub origin: ChangeOrigin,
+    suppressed: bool,
+}
+
+impl WatchEvent {
+    /// Whether the change is dropped instead of reported
+    pub(crate) fn is_suppressed(&self) -> bool {
+        self.suppressed
+    }
+}
+
+/// Event that can be merged with the events following it, see [`debounce`]
+pub trait Merge {
+    /// Fold a later event into this one
+    fn merge(&mut self, other: Self);
+}
+
+impl Merge for WatchEvent {
+    fn merge(&mut self, other: WatchEvent) {
+        self.merged += other.merged;
+        self.suppressed &= other.suppressed;
+
+        if other.origin == ChangeOrigin::External {
+            self.origin = ChangeOrigin::External;
+        }
+    }
+}
+
+/// Keeps the latest snapshot
+impl Merge for ClipboardChange {
+    fn merge(&mut self, other: ClipboardChange) {
+        self.merged += other.merged;
+        self.delta.merge(other.delta);
+        self.selection = other.selection;
+        self.cleared = other.cleared;
+        self.snapshot = other.snapshot;
+
+        if other.origin == ChangeOrigin::External {
+            self.origin = ChangeOrigin::External;
+        }
+    }
+}
+
+/// Write made through the manager, the next change is attributed to it
+pub(crate) struct SelfWrite {
+    at: Instant,
+    suppress: bool,
+}
+
+impl SelfWrite {
+    pub(crate) fn new(suppress: bool) -> Self {
+        Self {
+            at: Instant::now(),
+            suppress,
+        }
+    }
+}
+
+/// Attribute a clipboard change to the pending self write, if it happened within `window`
+pub(crate) fn change_event(self_write: &Mutex<Option<SelfWrite>>, window: Duration) -> WatchEvent {
+    match self_write.lock().take() {
+        Some(self_write) if self_write.at.elapsed() <= window => WatchEvent {
+            merged: 1,
+            origin: ChangeOrigin::Internal,
+            suppressed: self_write.suppress,
+        },
+        _ => WatchEvent {
+            merged: 1,
+            origin: ChangeOrigin::External,
+            suppressed: false,
+        },
+    }
+}
+
+/// Activity of a running watcher
+pub(crate) struct WatchState {
+    started_at: SystemTime,
+    polling: bool,
+    events: u64,
+    last_change_at: Option<SystemTime>,
+    paused: bool,
+    resume_at: Option<SystemTime>,
+}
+
+impl WatchState {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: SystemTime::now(),
+            polling: false,
+            events: 0,
+            last_change_at: None,
+            paused: false,
+            resume_at: None,
+        }
+    }
+
+    /// Record that changes are detected by polling
+    pub(crate) fn set_polling(&mut self, polling: bool) {
+        self.polling = polling;
+    }
+
+    /// Record a clipboard change
+    pub(crate) fn change(&mut self) {
+        self.last_change_at = Some(SystemTime::now());
+    }
+
+    /// Record a reported change, `false` if the watcher is paused and the change must be dropped
+    pub(crate) fn report(&mut self) -> bool {
+        if self.is_paused() {
+            return false;
+        }
+
+        self.events += 1;
+        true
+    }
+
+    /// Pause reporting changes, until `resume_after` has elapsed if given
+    pub(crate) fn pause(&mut self, resume_after: Option<Duration>) {
+        self.paused = true;
+        self.resume_at = resume_after.map(|resume_after| SystemTime::now() + resume_after);
+    }
+
+    pub(crate) fn resume(&mut self) {
+        self.paused = false;
+        self.resume_at = None;
+    }
+
+    /// Check if the watcher is paused, resuming it if its timer has elapsed
+    fn is_paused(&mut self) -> bool {
+        if let Some(resume_at) = self.resume_at {
+            if SystemTime::now() >= resume_at {
+                self.resume();
+            }
+        }
+
+        self.paused
+    }
+
+    pub(crate) fn status(&mut self) -> WatchStatus {
+        WatchStatus {
+            running: true,
+            polling: self.polling,
+            paused: self.is_paused(),
+            started_at: Some(timestamp(self.started_at)),
+            events: self.events,
+            last_change_at: self.last_change_at.map(timestamp),
+            resume_at: self.resume_at.map(timestamp),
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch
+fn timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Call `handler` whenever the clipboard fingerprint changes, checking every `interval`
+///
+/// Used when the backend cannot watch the clipboard, e.g. on some Linux desktops. Checks that fail
+/// are skipped, an unreachable clipboard is not a change.
+pub(crate) fn poll(
+    mut read_fingerprint: FingerprintReader,
+    interval: Duration,
+    mut handler: ChangeHandler,
+) -> WatchHandle {
+    let (stop, stopped) = channel::<()>();
+
+    spawn(move || {
+        let mut last_fingerprint = read_fingerprint().ok();
+
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            let Ok(current) = read_fingerprint() else {
+                continue;
+            };
+
+            if last_fingerprint.as_ref() != Some(&current) {
+                let changed = last_fingerprint.is_some();
+                last_fingerprint = Some(current);
+
+                if changed {
+                    handler();
+                }
+            }
+        }
+    });
+
+    WatchHandle::new(move || {
+        let _ = stop.send(());
+    })
+}
+
+/// Wrap `handler` so that changes closer together than `interval` call it only once, with the
+/// changes merged into one event
+///
+/// `handler` runs on a dedicated thread after the clipboard has been quiet for `interval`, or once
+/// `max_wait` has passed since the first change of a burst that keeps going. A burst still held when
+/// the returned handler is dropped is reported right away.
+pub fn debounce<E: Merge + Send + 'static>(
+    mut handler: Box<dyn FnMut(E) + Send + 'static>,
+    interval: Duration,
+    max_wait: Option<Duration>,
+) -> Box<dyn FnMut(E) + Send + 'static> {
+    let (sender, receiver) = channel::<E>();
+
+    spawn(move || {
+        while let Ok(mut event) = receiver.recv() {
+            let deadline = max_wait.map(|max_wait| Instant::now() + max_wait);
+
+            loop {
+                let timeout = match deadline {
+                    Some(deadline) => {
+                        interval.min(deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => interval,
+                };
+
+                match receiver.recv_timeout(timeout) {
+                    Ok(next_event) => {
+                        event.merge(next_event);
+
+                        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                            break;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        handler(event);
+                        return;
+                    }
+                }
+            }
+
+            handler(event);
+        }
+    });
+
+    Box::new(move |event| {
+        let _ = sender.send(event);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    fn event(origin: ChangeOrigin, suppressed: bool) -> WatchEvent {
+        WatchEvent {
+            merged: 1,
+            origin,
+            suppressed,
+        }
+    }
+
+    /// Report `count` changes `gap` apart through `debounce`, returning the merged counts
+    fn burst(count: u64, gap: Duration, max_wait: Option<Duration>) -> Vec<u64> {
+        let (sender, receiver) = channel();
+        let mut handler = debounce(
+            Box::new(move |event: WatchEvent| {
+                let _ = sender.send(event.merged);
+            }),
+            Duration::from_millis(100),
+            max_wait,
+        );
+
+        for _ in 0..count {
+            handler(event(ChangeOrigin::External, false));
+            sleep(gap);
+        }
+
+        sleep(Duration::from_millis(300));
+        receiver.try_iter().collect()
+    }
+
+    #[test]
+    fn debounce_merges_a_burst() {
+        assert_eq!(burst(10, Duration::from_millis(5), None), vec![10]);
+    }
+
+    #[test]
+    fn debounce_flushes_a_long_burst_after_max_wait() {
+        let merged = burst(
+            30,
+            Duration::from_millis(20),
+            Some(Duration::from_millis(150)),
+        );
+
+        assert!(merged.len() > 1, "{merged:?}");
+        assert_eq!(merged.iter().sum::<u64>(), 30);
+    }
+
+    #[test]
+    fn merged_events_are_external_and_reported_if_any_change_is() {
+        let mut merged = event(ChangeOrigin::Internal, true);
+        merged.merge(event(ChangeOrigin::Internal, true));
+        assert_eq!(merged.origin, ChangeOrigin::Internal);
+        assert!(merged.is_suppressed());
+
+        merged.merge(event(ChangeOrigin::External, false));
+        assert_eq!(merged.merged, 3);
+        assert_eq!(merged.origin, ChangeOrigin::External);
+        assert!(!merged.is_suppressed());
+    }
+
+    #[test]
+    fn debounce_flushes_the_held_event_when_dropped() {
+        let (sender, receiver) = channel();
+        let mut handler = debounce(
+            Box::new(move |event: WatchEvent| {
+                let _ = sender.send(event.merged);
+            }),
+            Duration::from_secs(10),
+            None,
+        );
+
+        handler(event(ChangeOrigin::External, false));
+        handler(event(ChangeOrigin::External, false));
+        drop(handler);
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(2)), Ok(2));
+    }
+}
@@ -0,0 +1,862 @@
+use crate::config::{OperationOptions, SnapshotOptions};
+use crate::manager::ClipboardNextManager;
+use crate::models::{
+    ChangeDelta, ChangeOrigin, ClipboardChange, ClipboardSnapshot, ClipboardStatus, Selection,
+    WatchStatus,
+};
+use crate::{Error, Result};
+use parking_lot::{Condvar, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, Builder};
+use std::time::{Duration, Instant};
+
+type Job = Box<dyn FnOnce(&ClipboardNextManager) + Send + 'static>;
+
+type ChangeCallback = Arc<Mutex<dyn FnMut(ClipboardChange) + Send + 'static>>;
+
+/// Managers of the selections other than the clipboard, created on first use
+type Selections = Mutex<HashMap<Selection, Arc<ClipboardNextManager>>>;
+
+struct Task {
+    job: Job,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// What the worker thread is doing, readable without going through the queue
+#[derive(Default)]
+struct Activity {
+    /// Operations sent to the worker and not taken yet
+    queued: AtomicUsize,
+    /// When the running operation started
+    running_since: Mutex<Option<Instant>>,
+}
+
+/// Runs every clipboard operation on a single dedicated thread
+///
+/// Operations are queued and executed one at a time, so a slow clipboard owner only delays the
+/// queue instead of blocking the caller's thread. Callers wait with a timeout and can cancel
+/// operations that have not started yet, transient failures are retried with backoff.
+///
+/// Clones share the same thread.
+#[derive(Clone)]
+pub struct ClipboardWorker {
+    manager: Arc<ClipboardNextManager>,
+    sender: Sender<Task>,
+    backlog: Arc<Mutex<VecDeque<ClipboardChange>>>,
+    selections: Arc<Selections>,
+    activity: Arc<Activity>,
+}
+
+/// Result of an operation submitted to a [`ClipboardWorker`]
+///
+/// Wait for it with [`Pending::wait`], or await it from async code: awaiting applies no timeout,
+/// wrap it with the async runtime's timeout using [`Pending::timeout`].
+pub struct Pending<T> {
+    reply: Arc<Reply<T>>,
+    cancelled: Arc<AtomicBool>,
+    timeout: Option<Duration>,
+}
+
+/// Slot the worker puts the result of an operation in
+struct Reply<T> {
+    state: Mutex<ReplyState<T>>,
+    ready: Condvar,
+}
+
+struct ReplyState<T> {
+    result: Option<Result<T>>,
+    /// The operation is done or was dropped without running
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// Fills the [`Reply`] once, closing it without a result if dropped first
+struct ReplySender<T>(Arc<Reply<T>>);
+
+/// Cancels the operation it was created for when dropped
+pub struct CancelGuard(Arc<AtomicBool>);
+
+impl ClipboardWorker {
+    /// Spawn the worker thread, timeouts and retries default to the manager's [`Config`](crate::Config)
+    pub fn new(manager: ClipboardNextManager) -> Self {
+        let manager = Arc::new(manager);
+        let (sender, receiver) = channel::<Task>();
+
+        let worker_manager = manager.clone();
+        let activity = Arc::new(Activity::default());
+        let worker_activity = activity.clone();
+
+        Builder::new()
+            .name("clipboard-next-worker".to_string())
+            .spawn(move || {
+                for task in receiver {
+                    worker_activity.queued.fetch_sub(1, Ordering::AcqRel);
+
+                    if task.cancelled.load(Ordering::Acquire) {
+                        continue;
+                    }
+
+                    *worker_activity.running_since.lock() = Some(Instant::now());
+
+                    // a panicking operation drops its reply channel, the worker keeps serving
+                    let _ = catch_unwind(AssertUnwindSafe(|| (task.job)(&worker_manager)));
+
+                    *worker_activity.running_since.lock() = None;
+                }
+            })
+            .expect("failed to spawn clipboard worker thread");
+
+        Self {
+            manager,
+            sender,
+            backlog: Arc::default(),
+            selections: Arc::default(),
+            activity,
+        }
+    }
+
+    /// The manager operations run against
+    ///
+    /// Calling clipboard operations on it directly bypasses the queue.
+    pub fn manager(&self) -> &ClipboardNextManager {
+        &self.manager
+    }
+
+    /// The latest change reported by [`ClipboardWorker::start_watch`], the contents when the watcher
+    /// started before the first change and `None` while it is stopped
+    pub fn last_change(&self) -> Option<ClipboardChange> {
+        self.backlog.lock().back().cloned()
+    }
+
+    /// The recent changes reported by [`ClipboardWorker::start_watch`], oldest first
+    ///
+    /// At most [`Config::backlog`](crate::Config::backlog) changes are kept.
+    pub fn backlog(&self) -> Vec<ClipboardChange> {
+        self.backlog.lock().iter().cloned().collect()
+    }
+
+    /// Status of the backend, read without queueing so it answers while an operation hangs
+    ///
+    /// The clipboard is reported unavailable while an operation has been running for longer than
+    /// [`Config::timeout`](crate::Config::timeout), e.g. because the clipboard owner does not answer.
+    pub fn status(&self) -> ClipboardStatus {
+        let timeout = self.manager.config().timeout;
+        let running_since = *self.activity.running_since.lock();
+
+        match running_since.map(|since| since.elapsed()) {
+            Some(running) if timeout > 0 && running > Duration::from_millis(timeout) => {
+                ClipboardStatus {
+                    available: false,
+                    error: Some(format!(
+                        "clipboard operation has been running for {}ms with {} queued behind it",
+                        running.as_millis(),
+                        self.activity.queued.load(Ordering::Acquire)
+                    )),
+                }
+            }
+            _ => self.manager.status(),
+        }
+    }
+
+    /// Queue an operation without waiting for it
+    pub fn submit<T, F>(&self, f: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnMut(&ClipboardNextManager) -> Result<T> + Send + 'static,
+    {
+        self.submit_with(&OperationOptions::default(), f)
+    }
+
+    /// Queue an operation with its own timeout and retry policy without waiting for it
+    ///
+    /// `f` is called again after a [transient](Error::is_transient) failure until the retries are
+    /// exhausted or the caller stopped waiting.
+    pub fn submit_with<T, F>(&self, options: &OperationOptions, mut f: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnMut(&ClipboardNextManager) -> Result<T> + Send + 'static,
+    {
+        let config = self.manager.config();
+        let selection = options.selection.unwrap_or_default();
+        let selections = self.selections.clone();
+        let suppress_echo = options.suppress_echo;
+        let retry = options.retry.unwrap_or(config.retry);
+        let timeout = match options.timeout.unwrap_or(config.timeout) {
+            0 => None,
+            timeout => Some(Duration::from_millis(timeout)),
+        };
+
+        let reply = Arc::new(Reply::new());
+        let result_sender = ReplySender(reply.clone());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let job_cancelled = cancelled.clone();
+
+        let task = Task {
+            job: Box::new(move |manager| {
+                let selection_manager;
+                let manager = match selection {
+                    Selection::Clipboard => manager,
+                    selection => match selection_manager_of(&selections, manager, selection) {
+                        Ok(manager) => {
+                            selection_manager = manager;
+                            &selection_manager
+                        }
+                        Err(err) => {
+                            result_sender.send(Err(err));
+                            return;
+                        }
+                    },
+                };
+
+                let mut attempt = 0;
+
+                let result = manager.with_suppress_echo(suppress_echo, || loop {
+                    match f(manager) {
+                        Err(err)
+                            if err.is_transient()
+                                && attempt < retry.max_retries
+                                && !job_cancelled.load(Ordering::Acquire) =>
+                        {
+                            thread::sleep(retry.delay(attempt));
+                            attempt += 1;
+                        }
+                        result => break result,
+                    }
+                });
+
+                result_sender.send(result);
+            }),
+            cancelled: cancelled.clone(),
+        };
+
+        self.activity.queued.fetch_add(1, Ordering::AcqRel);
+
+        // if the worker is gone the reply is closed with the dropped task,
+        // which `Pending` reports as unavailable
+        if self.sender.send(task).is_err() {
+            self.activity.queued.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        Pending {
+            reply,
+            cancelled,
+            timeout,
+        }
+    }
+
+    /// Capture a snapshot on every clipboard change and pass it to `handler`
+    ///
+    /// Snapshots are captured on the worker thread, changes whose snapshot cannot be captured are
+    /// not reported. Each snapshot is compared with the previous one, starting with the contents
+    /// when the watcher started, to tell which formats changed. Those contents and the reported
+    /// changes are kept in the [`backlog`](ClipboardWorker::backlog) until the watcher stops.
+    ///
+    /// Every selection of [`Config::watch_selections`](crate::Config::watch_selections) is watched,
+    /// [`ClipboardChange::selection`] tells which one changed.
+    pub fn start_watch<F>(&self, options: SnapshotOptions, handler: F) -> Result<()>
+    where
+        F: FnMut(ClipboardChange) + Send + 'static,
+    {
+        let handler: ChangeCallback = Arc::new(Mutex::new(handler));
+        let mut started: Vec<Selection> = Vec::new();
+
+        let mut selections = self.manager.config().watch_selections.clone();
+
+        if selections.is_empty() {
+            selections.push(Selection::Clipboard);
+        }
+
+        for selection in selections {
+            if started.contains(&selection) {
+                continue;
+            }
+
+            if let Err(err) = self.start_watch_selection(selection, &options, handler.clone()) {
+                for selection in started {
+                    if let Some(manager) = self.selection_manager(selection) {
+                        let _ = manager.stop_watch();
+                    }
+                }
+
+                // the backlog belongs to the watcher that is already running
+                if !matches!(err, Error::WatcherAlreadyRunning) {
+                    self.backlog.lock().clear();
+                }
+
+                return Err(err);
+            }
+
+            started.push(selection);
+        }
+
+        Ok(())
+    }
+
+    /// Stop the watchers started by [`ClipboardWorker::start_watch`]
+    ///
+    /// Like [`ClipboardNextManager::stop_watch`] it does not wait for the queue. The
+    /// [`backlog`](ClipboardWorker::backlog) is cleared.
+    pub fn stop_watch(&self) -> Result<()> {
+        let result = self.each_manager(|manager| manager.stop_watch());
+        self.backlog.lock().clear();
+        result
+    }
+
+    /// Stop reporting changes of every watched selection, see [`ClipboardNextManager::pause_watch`]
+    pub fn pause_watch(&self, resume_after: Option<Duration>) -> Result<()> {
+        self.each_manager(|manager| manager.pause_watch(resume_after))
+    }
+
+    pub fn resume_watch(&self) -> Result<()> {
+        self.each_manager(|manager| manager.resume_watch())
+    }
+
+    /// State of the watcher, of the first watched selection if the clipboard is not watched
+    pub fn watch_status(&self) -> WatchStatus {
+        let status = self.manager.watch_status();
+
+        if status.running {
+            return status;
+        }
+
+        self.selections
+            .lock()
+            .values()
+            .map(|manager| manager.watch_status())
+            .find(|status| status.running)
+            .unwrap_or(status)
+    }
+
+    fn start_watch_selection(
+        &self,
+        selection: Selection,
+        options: &SnapshotOptions,
+        handler: ChangeCallback,
+    ) -> Result<()> {
+        let worker = self.clone();
+        let options = Arc::new(options.clone());
+        let previous = Arc::new(Mutex::new(None::<ClipboardSnapshot>));
+        let backlog = self.backlog.clone();
+        let operation = OperationOptions {
+            selection: Some(selection),
+            ..OperationOptions::default()
+        };
+
+        self.run_with(&operation.clone(), move |manager| {
+            let worker = worker.clone();
+            let options = options.clone();
+            let handler = handler.clone();
+            let previous = previous.clone();
+            let backlog = backlog.clone();
+            let operation = operation.clone();
+
+            // checked before the snapshot, the running watcher keeps its backlog
+            if manager.watch_status().running {
+                return Err(Error::WatcherAlreadyRunning);
+            }
+
+            *previous.lock() = manager.snapshot(&options).ok();
+            let seed = previous.lock().clone();
+
+            // polled checks are queued like any other operation instead of racing the worker
+            let poll_worker = worker.clone();
+            let poll_operation = operation.clone();
+            let read_fingerprint = Box::new(move || {
+                poll_worker.run_with(&poll_operation, |manager| manager.fingerprint())
+            });
+
+            let change_backlog = backlog.clone();
+
+            manager.start_watch_with(
+                Box::new(move |event| {
+                    let options = options.clone();
+                    let handler = handler.clone();
+                    let previous = previous.clone();
+                    let backlog = change_backlog.clone();
+
+                    // not waited for, changes can be reported while a write is running on the worker
+                    drop(worker.submit_with(&operation, move |manager| {
+                        let snapshot = manager.snapshot(&options)?;
+                        let cleared = manager.is_empty()?;
+                        let delta = ChangeDelta::between(previous.lock().as_ref(), &snapshot);
+                        *previous.lock() = Some(snapshot.clone());
+
+                        let change = ClipboardChange {
+                            merged: event.merged,
+                            origin: event.origin,
+                            selection,
+                            cleared,
+                            delta,
+                            snapshot,
+                        };
+
+                        keep(&backlog, change.clone(), manager.config().backlog);
+                        (handler.lock())(change);
+                        Ok(())
+                    }));
+                }),
+                read_fingerprint,
+            )?;
+
+            // seeded once started so late listeners get the current contents before the first
+            // change, which is queued behind this operation
+            if let Some(snapshot) = seed {
+                let change = ClipboardChange {
+                    merged: 0,
+                    origin: ChangeOrigin::External,
+                    selection,
+                    cleared: manager.is_empty().unwrap_or_default(),
+                    delta: ChangeDelta::between(None, &snapshot),
+                    snapshot,
+                };
+
+                keep(&backlog, change, manager.config().backlog);
+            }
+
+            Ok(())
+        })
+    }
+
+    /// The manager of `selection` if it has been used
+    fn selection_manager(&self, selection: Selection) -> Option<Arc<ClipboardNextManager>> {
+        match selection {
+            Selection::Clipboard => Some(self.manager.clone()),
+            selection => self.selections.lock().get(&selection).cloned(),
+        }
+    }
+
+    /// Call `f` with the manager of every selection that has been used, succeeding if any call does
+    fn each_manager<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&ClipboardNextManager) -> Result<()>,
+    {
+        let managers: Vec<_> = self.selections.lock().values().cloned().collect();
+        let mut result = f(&self.manager);
+
+        for manager in managers {
+            if f(&manager).is_ok() {
+                result = Ok(());
+            }
+        }
+
+        result
+    }
+
+    /// Queue an operation and wait for it
+    pub fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnMut(&ClipboardNextManager) -> Result<T> + Send + 'static,
+    {
+        self.submit(f).wait()
+    }
+
+    /// Queue an operation with its own timeout and retry policy and wait for it
+    pub fn run_with<T, F>(&self, options: &OperationOptions, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnMut(&ClipboardNextManager) -> Result<T> + Send + 'static,
+    {
+        self.submit_with(options, f).wait()
+    }
+}
+
+impl<T> Pending<T> {
+    /// Skip the operation if it has not started yet and stop retrying it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Guard that cancels the operation when dropped, e.g. when an async caller goes away
+    pub fn cancel_guard(&self) -> CancelGuard {
+        CancelGuard(self.cancelled.clone())
+    }
+
+    /// Time to wait for the operation, `None` waits forever
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Wait for the result until the operation's timeout, blocking the current thread
+    ///
+    /// On timeout the operation is cancelled.
+    pub fn wait(self) -> Result<T> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.reply.state.lock();
+
+        while !state.closed {
+            match deadline {
+                Some(deadline) => {
+                    if self
+                        .reply
+                        .ready
+                        .wait_until(&mut state, deadline)
+                        .timed_out()
+                    {
+                        break;
+                    }
+                }
+                None => self.reply.ready.wait(&mut state),
+            }
+        }
+
+        match state.result.take() {
+            Some(result) => result,
+            None if !state.closed => {
+                self.cancel();
+                Err(Error::Timeout(self.timeout.unwrap_or_default()))
+            }
+            None => Err(self.closed_error()),
+        }
+    }
+
+    fn closed_error(&self) -> Error {
+        if self.cancelled.load(Ordering::Acquire) {
+            Error::Cancelled
+        } else {
+            Error::BackendUnavailable("clipboard worker stopped".to_string())
+        }
+    }
+}
+
+impl<T> Future for Pending<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T>> {
+        let mut state = self.reply.state.lock();
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None if state.closed => Poll::Ready(Err(self.closed_error())),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Reply<T> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(ReplyState {
+                result: None,
+                closed: false,
+                waker: None,
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    fn close(&self, result: Option<Result<T>>) {
+        let mut state = self.state.lock();
+
+        if state.closed {
+            return;
+        }
+
+        state.result = result;
+        state.closed = true;
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+
+        self.ready.notify_all();
+    }
+}
+
+impl<T> ReplySender<T> {
+    fn send(self, result: Result<T>) {
+        self.0.close(Some(result));
+    }
+}
+
+impl<T> Drop for ReplySender<T> {
+    fn drop(&mut self) {
+        self.0.close(None);
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// Add a change to the backlog, dropping the oldest changes beyond `size`
+fn keep(backlog: &Mutex<VecDeque<ClipboardChange>>, change: ClipboardChange, size: usize) {
+    let mut backlog = backlog.lock();
+
+    backlog.push_back(change);
+
+    while backlog.len() > size.max(1) {
+        backlog.pop_front();
+    }
+}
+
+/// The manager of `selection`, created from the clipboard `manager` on first use
+fn selection_manager_of(
+    selections: &Selections,
+    manager: &ClipboardNextManager,
+    selection: Selection,
+) -> Result<Arc<ClipboardNextManager>> {
+    let mut selections = selections.lock();
+
+    if let Some(manager) = selections.get(&selection) {
+        return Ok(manager.clone());
+    }
+
+    let selection_manager = Arc::new(manager.for_selection(selection)?);
+    selections.insert(selection, selection_manager.clone());
+
+    Ok(selection_manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChangeOrigin, ClipboardFormat};
+    use crate::{Config, MemoryBackend};
+    use clipboard_rs::ClipboardContent;
+    use std::sync::mpsc::Receiver;
+
+    fn watch(backend: &MemoryBackend) -> (ClipboardWorker, Receiver<ClipboardChange>) {
+        watch_with(backend, Config::default())
+    }
+
+    fn watch_with(
+        backend: &MemoryBackend,
+        config: Config,
+    ) -> (ClipboardWorker, Receiver<ClipboardChange>) {
+        let manager = ClipboardNextManager::with_config(Box::new(backend.clone()), config);
+        let worker = ClipboardWorker::new(manager);
+        let (sender, receiver) = channel();
+
+        worker
+            .start_watch(SnapshotOptions::default(), move |change| {
+                let _ = sender.send(change);
+            })
+            .unwrap();
+
+        (worker, receiver)
+    }
+
+    fn next(receiver: &Receiver<ClipboardChange>) -> ClipboardChange {
+        receiver.recv_timeout(Duration::from_secs(2)).unwrap()
+    }
+
+    #[test]
+    fn start_watch_reports_changes_with_delta() {
+        let backend = MemoryBackend::new();
+        backend.set_external(vec![ClipboardContent::Text("a".into())]);
+        let (worker, receiver) = watch(&backend);
+
+        backend.set_external(vec![
+            ClipboardContent::Text("b".into()),
+            ClipboardContent::Html("<b>b</b>".into()),
+        ]);
+
+        let change = next(&receiver);
+        assert_eq!(change.merged, 1);
+        assert_eq!(change.origin, ChangeOrigin::External);
+        assert!(!change.is_cleared());
+        assert_eq!(change.delta.added, vec![ClipboardFormat::Html]);
+        assert_eq!(change.delta.changed, vec![ClipboardFormat::Text]);
+        assert_eq!(change.snapshot.text.as_deref(), Some("b"));
+        assert_eq!(change.snapshot.html.as_deref(), Some("<b>b</b>"));
+
+        backend.clear_external();
+
+        let change = next(&receiver);
+        assert!(change.is_cleared());
+        assert_eq!(
+            change.delta.removed,
+            vec![ClipboardFormat::Text, ClipboardFormat::Html]
+        );
+
+        worker.run(|manager| manager.stop_watch()).unwrap();
+    }
+
+    #[test]
+    fn start_watch_labels_own_writes() {
+        let backend = MemoryBackend::new();
+        let (worker, receiver) = watch(&backend);
+
+        worker
+            .run(|manager| manager.write_text("mine".into()))
+            .unwrap();
+        assert_eq!(next(&receiver).origin, ChangeOrigin::Internal);
+
+        backend.set_external(vec![ClipboardContent::Text("theirs".into())]);
+        assert_eq!(next(&receiver).origin, ChangeOrigin::External);
+
+        worker.run(|manager| manager.stop_watch()).unwrap();
+    }
+
+    #[test]
+    fn start_watch_drops_suppressed_writes() {
+        let backend = MemoryBackend::new();
+        let (worker, receiver) = watch(&backend);
+        let options = OperationOptions {
+            suppress_echo: Some(true),
+            ..OperationOptions::default()
+        };
+
+        worker
+            .run_with(&options, |manager| manager.write_text("mine".into()))
+            .unwrap();
+        backend.set_external(vec![ClipboardContent::Text("theirs".into())]);
+
+        let change = next(&receiver);
+        assert_eq!(change.origin, ChangeOrigin::External);
+        assert_eq!(change.snapshot.text.as_deref(), Some("theirs"));
+
+        worker.run(|manager| manager.stop_watch()).unwrap();
+    }
+
+    #[test]
+    fn backlog_keeps_the_latest_changes() {
+        let backend = MemoryBackend::new();
+        let config = Config {
+            backlog: 2,
+            ..Config::default()
+        };
+        let (worker, receiver) = watch_with(&backend, config);
+
+        for text in ["a", "b", "c"] {
+            backend.set_external(vec![ClipboardContent::Text(text.into())]);
+            next(&receiver);
+        }
+
+        let texts: Vec<_> = worker
+            .backlog()
+            .into_iter()
+            .map(|change| change.snapshot.text.unwrap())
+            .collect();
+        assert_eq!(texts, ["b", "c"]);
+        assert_eq!(
+            worker.last_change().unwrap().snapshot.text.as_deref(),
+            Some("c")
+        );
+
+        worker.run(|manager| manager.stop_watch()).unwrap();
+    }
+
+    #[test]
+    fn unsupported_selections_fail_without_watching() {
+        let backend = MemoryBackend::new();
+        let config = Config {
+            watch_selections: vec![Selection::Clipboard, Selection::Primary],
+            ..Config::default()
+        };
+        let worker =
+            ClipboardWorker::new(ClipboardNextManager::with_config(Box::new(backend), config));
+        let primary = OperationOptions {
+            selection: Some(Selection::Primary),
+            ..OperationOptions::default()
+        };
+
+        let err = worker
+            .run_with(&primary, |manager| manager.read_text())
+            .unwrap_err();
+        assert_eq!(err.code(), "selectionUnsupported");
+
+        let err = worker
+            .start_watch(SnapshotOptions::default(), |_| {})
+            .unwrap_err();
+        assert_eq!(err.code(), "selectionUnsupported");
+        assert!(!worker.watch_status().running);
+    }
+
+    #[test]
+    fn backlog_holds_the_contents_until_the_watcher_stops() {
+        let backend = MemoryBackend::new();
+        backend.set_external(vec![ClipboardContent::Text("a".into())]);
+        let (worker, _receiver) = watch(&backend);
+
+        let change = worker.last_change().unwrap();
+        assert_eq!(change.merged, 0);
+        assert_eq!(change.delta.added, vec![ClipboardFormat::Text]);
+
+        worker.stop_watch().unwrap();
+        assert!(worker.last_change().is_none());
+    }
+
+    #[test]
+    fn starting_a_running_watcher_keeps_its_backlog() {
+        let backend = MemoryBackend::new();
+        let config = Config {
+            backlog: 10,
+            ..Config::default()
+        };
+        let (worker, receiver) = watch_with(&backend, config);
+        backend.set_external(vec![ClipboardContent::Text("a".into())]);
+        next(&receiver);
+
+        let err = worker
+            .start_watch(SnapshotOptions::default(), |_| {})
+            .unwrap_err();
+        assert_eq!(err.code(), "watcherAlreadyRunning");
+
+        let texts: Vec<_> = worker
+            .backlog()
+            .into_iter()
+            .map(|change| change.snapshot.text)
+            .collect();
+        assert_eq!(texts, [None, Some("a".to_string())]);
+
+        worker.stop_watch().unwrap();
+    }
+
+    #[test]
+    fn clearing_is_reported_with_suppress_echo() {
+        let backend = MemoryBackend::new();
+        backend.set_external(vec![ClipboardContent::Text("a".into())]);
+        let config = Config {
+            suppress_echo: true,
+            ..Config::default()
+        };
+        let (worker, receiver) = watch_with(&backend, config);
+
+        worker.run(|manager| manager.clear()).unwrap();
+
+        let change = next(&receiver);
+        assert_eq!(change.origin, ChangeOrigin::Internal);
+        assert!(change.cleared);
+
+        worker.stop_watch().unwrap();
+    }
+
+    #[test]
+    fn status_reports_an_operation_running_past_the_timeout() {
+        let config = Config {
+            timeout: 50,
+            ..Config::default()
+        };
+        let manager = ClipboardNextManager::with_config(Box::new(MemoryBackend::new()), config);
+        let worker = ClipboardWorker::new(manager);
+        assert!(worker.status().available);
+
+        let _hung = worker.submit(|_| {
+            thread::sleep(Duration::from_millis(200));
+            Ok(())
+        });
+        let _queued = worker.submit(|_| Ok(()));
+        thread::sleep(Duration::from_millis(100));
+
+        let status = worker.status();
+        assert!(!status.available);
+        assert!(status.error.unwrap().contains("1 queued"));
+
+        thread::sleep(Duration::from_millis(300));
+        assert!(worker.status().available);
+    }
+}
@@ -4,5 +4,5 @@ use tauri_plugin_clipboard_next::ClipboardNextExt;
 
 #[tauri::command]
 pub async fn read_text(app_handle: AppHandle) -> Result<String> {
-    app_handle.clipboard_next().read_text()
+    app_handle.clipboard_next().read_text(None).await
 }
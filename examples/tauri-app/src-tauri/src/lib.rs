@@ -2,7 +2,7 @@ use tauri::Manager;
 
 mod commands;
 
-type Result<T> = std::result::Result<T, String>;
+type Result<T> = tauri_plugin_clipboard_next::Result<T>;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
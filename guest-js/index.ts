@@ -1,5 +1,5 @@
-import { invoke } from '@tauri-apps/api/core'
-import { listen } from "@tauri-apps/api/event";
+import { Channel, invoke } from '@tauri-apps/api/core'
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 
 const buildCmd = (cmd: string) => `plugin:clipboard-next|${cmd}`
 const buildEventUrl = (event: string) => `plugin:clipboard-next://${event}`
@@ -7,27 +7,98 @@ const buildEventUrl = (event: string) => `plugin:clipboard-next://${event}`
 export const COMMANDS = {
   START_WATCH: buildCmd("start_watch"),
   STOP_WATCH: buildCmd("stop_watch"),
+  PAUSE_WATCH: buildCmd("pause_watch"),
+  RESUME_WATCH: buildCmd("resume_watch"),
+  WATCH_STATUS: buildCmd("watch_status"),
+  GET_LAST_CHANGE: buildCmd("get_last_change"),
+  SUBSCRIBE: buildCmd("subscribe"),
+  UNSUBSCRIBE: buildCmd("unsubscribe"),
   HAS_TEXT: buildCmd("has_text"),
   HAS_RTF: buildCmd("has_rtf"),
   HAS_HTML: buildCmd("has_html"),
   HAS_IMAGE: buildCmd("has_image"),
   HAS_FILES: buildCmd("has_files"),
+  HAS_FORMAT: buildCmd("has_format"),
   READ_TEXT: buildCmd("read_text"),
   READ_RTF: buildCmd("read_rtf"),
   READ_HTML: buildCmd("read_html"),
   READ_IMAGE: buildCmd("read_image"),
   READ_FILES: buildCmd("read_files"),
+  READ_FORMAT: buildCmd("read_format"),
+  READ_ALL: buildCmd("read_all"),
   WRITE_TEXT: buildCmd("write_text"),
   WRITE_RTF: buildCmd("write_rtf"),
   WRITE_HTML: buildCmd("write_html"),
   WRITE_IMAGE: buildCmd("write_image"),
   WRITE_FILES: buildCmd("write_files"),
+  WRITE_FORMAT: buildCmd("write_format"),
+  WRITE: buildCmd("write"),
   CLEAR: buildCmd("clear"),
-  GET_FILE_PATH: buildCmd("get_file_path")
+  AVAILABLE_FORMATS: buildCmd("available_formats"),
+  CHANGE_COUNT: buildCmd("change_count"),
+  FINGERPRINT: buildCmd("fingerprint"),
+  GET_FILE_PATH: buildCmd("get_file_path"),
+  STATUS: buildCmd("status"),
 }
 
 export const EVENTS = {
   CLIPBOARD_CHANGE: buildEventUrl("clipboard_change"),
+  CLIPBOARD_CLEARED: buildEventUrl("clipboard_cleared"),
+}
+
+export type ClipboardErrorCode =
+  | "formatUnavailable"
+  | "formatDisabled"
+  | "sizeLimitExceeded"
+  | "backendUnavailable"
+  | "backend"
+  | "io"
+  | "imageDecode"
+  | "invalidPath"
+  | "watcherAlreadyRunning"
+  | "watcherNotRunning"
+  | "timeout"
+  | "cancelled"
+  | "selectionUnsupported"
+  | "tauri"
+  | "invalidRequest";
+
+export interface ClipboardError {
+  /**
+   * @descCN 错误类型，`formatUnavailable` 表示剪贴板中没有该格式的内容
+   * @descEN The kind of error, `formatUnavailable` means the clipboard does not contain that format
+   */
+  code: ClipboardErrorCode;
+  /**
+   * @descCN 错误信息
+   * @descEN The error message
+   */
+  message: string;
+  /**
+   * @descCN 附加信息，例如缺失的格式或出错的路径
+   * @descEN Additional information, e.g. the missing format or the offending path
+   */
+  details: string | null;
+}
+
+/**
+ * Check if a rejected command value is a clipboard error
+ *
+ * @example
+ * ```
+ * import { readText, isClipboardError } from 'tauri-plugin-clipboard-next-api';
+ *
+ * try {
+ *   await readText();
+ * } catch (err) {
+ *   if (isClipboardError(err) && err.code === "formatUnavailable") {
+ *     // the clipboard does not contain text
+ *   }
+ * }
+ * ```
+ */
+export const isClipboardError = (err: unknown): err is ClipboardError => {
+  return typeof err === "object" && err !== null && "code" in err && "message" in err
 }
 
 export interface ReadImage {
@@ -79,8 +150,303 @@ export interface ReadFiles {
   size: number;
 }
 
+export interface WatchStatus {
+  /**
+   * @descCN 是否正在监听剪贴板变化
+   * @descEN Whether the watcher is running
+   */
+  running: boolean;
+  /**
+   * @descCN 是否通过轮询检测剪贴板变化，在无法使用系统通知或设置了 `watchMode: "polling"` 时为 `true`
+   * @descEN Whether changes are detected by polling, because native notifications are unavailable or `watchMode` is `"polling"`
+   */
+  polling: boolean;
+  /**
+   * @descCN 是否已暂停，暂停期间不会通知剪贴板变化
+   * @descEN Whether the watcher is paused, changes are not reported while paused
+   */
+  paused: boolean;
+  /**
+   * @descCN 开始监听的时间，Unix 毫秒时间戳
+   * @descEN When the watcher started, in milliseconds since the Unix epoch
+   */
+  startedAt: number | null;
+  /**
+   * @descCN 开始监听后通知的变化次数
+   * @descEN Number of changes reported since the watcher started
+   */
+  events: number;
+  /**
+   * @descCN 最后一次剪贴板变化的时间，包括暂停期间的变化，Unix 毫秒时间戳
+   * @descEN When the clipboard last changed, including while paused, in milliseconds since the Unix epoch
+   */
+  lastChangeAt: number | null;
+  /**
+   * @descCN 自动恢复监听的时间，Unix 毫秒时间戳
+   * @descEN When the watcher resumes on its own, in milliseconds since the Unix epoch
+   */
+  resumeAt: number | null;
+}
+
+export interface ClipboardStatus {
+  /**
+   * @descCN 剪贴板是否可用
+   * @descEN Whether the clipboard can be reached
+   */
+  available: boolean;
+  /**
+   * @descCN 剪贴板不可用的原因
+   * @descEN The reason why the clipboard cannot be reached
+   */
+  error: string | null;
+}
+
+export interface FormatInfo {
+  /**
+   * @descCN 平台报告的格式名，例如 MIME 类型
+   * @descEN The format name as reported by the platform, e.g. a MIME type
+   */
+  name: string;
+  /**
+   * @descCN 该格式对应的内置格式，自定义格式为 null
+   * @descEN The built-in format the name stands for, null for custom formats
+   */
+  format: ClipboardContentFormat | null;
+  /**
+   * @descCN 内容的字节大小，未请求大小、格式为图片或平台无法提供时为 null
+   * @descEN The size of the content in bytes, null unless sizes were requested, for images, or if the platform cannot report it
+   */
+  size: number | null;
+}
+
+/**
+ * @descCN 写入剪贴板的一种内容表示，image 需要提供 path 或 bytes 之一
+ * @descEN One representation of the content written by `write`, an image needs either a path or bytes
+ */
+export type Representation =
+  | { type: "text"; content: string }
+  | { type: "rtf"; content: string }
+  | { type: "html"; content: string }
+  | { type: "image"; path?: string; bytes?: Uint8Array | ArrayBuffer | number[] }
+  | { type: "files"; paths: string[] }
+  | { type: "custom"; format: string; bytes: Uint8Array | ArrayBuffer | number[] };
+
+/**
+ * @descCN X11 选区，`primary` 为选中的文本（鼠标中键粘贴），仅 Linux 支持 `clipboard` 以外的选区
+ * @descEN X11 selection, `primary` holds the selected text pasted with a middle click, only Linux has selections other than `clipboard`
+ */
+export type Selection = "clipboard" | "primary" | "secondary";
+
+export interface RetryPolicy {
+  /**
+   * default value: `2`
+   * @descCN 首次尝试失败后的重试次数，`0` 表示不重试
+   * @descEN Number of retries after the first attempt, `0` disables retries
+   */
+  maxRetries?: number;
+  /**
+   * default value: `50`
+   * @descCN 第一次重试前的等待时间，以毫秒为单位，之后每次翻倍
+   * @descEN Delay before the first retry in milliseconds, doubled after every attempt
+   */
+  backoff?: number;
+  /**
+   * default value: `1000`
+   * @descCN 两次重试之间的最长等待时间，以毫秒为单位
+   * @descEN Maximum delay between two retries in milliseconds
+   */
+  maxBackoff?: number;
+}
+
+export interface OperationOptions {
+  /**
+   * @descCN 操作的超时时间，以毫秒为单位，`0` 表示一直等待，默认使用插件配置
+   * @descEN Time to wait for the operation in milliseconds, `0` waits forever, defaults to the plugin config
+   */
+  timeout?: number;
+  /**
+   * @descCN 临时失败的重试策略，默认使用插件配置
+   * @descEN Retry policy for transient failures, defaults to the plugin config
+   */
+  retry?: RetryPolicy;
+  /**
+   * @descCN 仅用于写入，不通知本次写入引起的剪贴板变化，默认使用插件配置
+   * @descEN Writes only, do not report the clipboard change caused by this write, defaults to the plugin config
+   */
+  suppressEcho?: boolean;
+  /**
+   * default value: `"clipboard"`
+   * @descCN 读取或写入的选区，其他平台上非 `clipboard` 的选区返回 `selectionUnsupported` 错误
+   * @descEN The selection to read or write, selections other than `clipboard` reject with `selectionUnsupported` on other platforms
+   */
+  selection?: Selection;
+}
+
 export type ClipboardContentFormat = "text" | "rtf" | "html" | "image" | "files";
 
+export interface ImageInfo {
+  /**
+   * @descCN 图像的宽度
+   * @descEN The width of the image
+   */
+  width: number;
+  /**
+   * @descCN 图像的高度
+   * @descEN The height of the image
+   */
+  height: number;
+  /**
+   * @descCN 图像保存的路径，未保存时为 `null`
+   * @descEN The path the image was saved to, `null` if it was not saved
+   */
+  path: string | null;
+  /**
+   * @descCN 保存的图像文件大小，以字节为单位
+   * @descEN The size of the saved image file in bytes
+   */
+  size: number | null;
+}
+
+export interface ClipboardSnapshot {
+  /**
+   * @descCN 剪贴板包含的格式，包括未读取内容的格式
+   * @descEN The formats the clipboard contains, including formats whose content was not captured
+   */
+  formats: ClipboardContentFormat[];
+  text: string | null;
+  rtf: string | null;
+  html: string | null;
+  image: ImageInfo | null;
+  files: ReadFiles | null;
+}
+
+export interface ChangeDelta {
+  /**
+   * @descCN 变化前剪贴板中没有的格式
+   * @descEN Formats the clipboard did not contain before
+   */
+  added: ClipboardContentFormat[];
+  /**
+   * @descCN 变化后剪贴板中不再包含的格式
+   * @descEN Formats the clipboard no longer contains
+   */
+  removed: ClipboardContentFormat[];
+  /**
+   * @descCN 内容发生变化的格式，未读取内容的格式不会出现在这里
+   * @descEN Formats whose content changed, formats whose content is not captured never appear here
+   */
+  changed: ClipboardContentFormat[];
+}
+
+export interface ClipboardChange extends ClipboardSnapshot {
+  /**
+   * @descCN 合并为本次通知的剪贴板变化次数，未设置`debounceInterval`时为 `1`，监听开始时保存的内容为 `0`
+   * @descEN Number of clipboard changes merged into this event, `1` without `debounceInterval`, `0` for the contents kept when the watcher started
+   */
+  merged: number;
+  /**
+   * @descCN 变化的来源，`self` 表示由本应用写入，`external` 表示由其他应用写入
+   * @descEN Where the change came from, `self` for the app's own writes, `external` for other applications
+   */
+  origin: "self" | "external";
+  /**
+   * @descCN 发生变化的选区，见插件配置 `watchSelections`
+   * @descEN The selection that changed, see the `watchSelections` plugin config
+   */
+  selection: Selection;
+  /**
+   * @descCN 变化后剪贴板中是否没有任何格式，包括未启用的格式和自定义格式
+   * @descEN Whether the clipboard holds no format at all after the change, including disabled and custom ones
+   */
+  cleared: boolean;
+  /**
+   * @descCN 与上一次变化相比出现、消失或改变的格式
+   * @descEN Formats that appeared, disappeared or changed since the previous change
+   */
+  delta: ChangeDelta;
+}
+
+export interface SnapshotOptions {
+  /**
+   * @descCN 需要读取内容的格式，默认读取所有启用的格式
+   * @descEN The formats whose content is captured, defaults to every enabled format
+   */
+  formats?: ClipboardContentFormat[];
+  /**
+   * default value: `true`
+   * @descCN 保存图片到`imageDir`，否则只读取图片的尺寸
+   * @descEN Save the image to `imageDir`, otherwise only its size is captured
+   */
+  saveImage?: boolean;
+  /**
+   * @descCN 保存图片的路径，如果未提供则使用默认路径，使用`getFilePath()`获取默认路径位置
+   * @descEN The path to save the image, if not provided, the default path will be used, use `getFilePath()` to get the default path
+   */
+  imageDir?: string;
+}
+
+export interface ChangeFilter {
+  /**
+   * @descCN 只通知剪贴板中包含这些格式之一的变化
+   * @descEN Only report changes that leave any of these formats on the clipboard
+   */
+  formats?: ClipboardContentFormat[];
+  /**
+   * @descCN 文本的最小字节数，不包含文本的变化不受影响
+   * @descEN Minimum size of the text in bytes, changes without text are not affected
+   */
+  minTextSize?: number;
+  /**
+   * @descCN 文本的最大字节数，不包含文本的变化不受影响
+   * @descEN Maximum size of the text in bytes, changes without text are not affected
+   */
+  maxTextSize?: number;
+}
+
+export interface SubscribeOptions {
+  /**
+   * @descCN 通知的格式，默认为监听读取的所有格式
+   * @descEN The formats reported to the subscriber, defaults to every format captured by the watcher
+   */
+  formats?: ClipboardContentFormat[];
+  /**
+   * @descCN 需要通知的变化，默认通知所有变化
+   * @descEN The changes sent to the subscriber, defaults to every change
+   */
+  filter?: ChangeFilter;
+  /**
+   * default value: `0`
+   * @descCN 间隔小于该值（毫秒）的变化只通知一次
+   * @descEN Changes closer together than this interval (ms) are reported once
+   */
+  debounce?: number;
+  /**
+   * default value: `true`
+   * @descCN 通知剪贴板的内容，否则只通知剪贴板包含的格式
+   * @descEN Send the clipboard contents, otherwise only the formats the clipboard contains
+   */
+  includeContent?: boolean;
+  /**
+   * default value: `false`
+   * @descCN 订阅后先发送插件保存的最近变化，参见 `backlog` 配置
+   * @descEN Send the recent changes kept by the plugin first, see the `backlog` config
+   */
+  replay?: boolean;
+}
+
+export interface Subscription {
+  /**
+   * @descCN 订阅的 id
+   * @descEN The id of the subscription
+   */
+  id: number;
+  /**
+   * @descCN 停止订阅
+   * @descEN Stop the subscription
+   */
+  unsubscribe: () => Promise<void>;
+}
+
 type ClipboardContentValue<T extends ClipboardContentFormat> =
   T extends "image" ? ReadImage :
   T extends "files" ? ReadFiles :
@@ -102,20 +468,25 @@ export type ClipboardContent<T extends ClipboardContentFormat = ClipboardContent
 
 export type ReadClipboard = Partial<{ [K in ClipboardContentFormat]: ClipboardContent<K> }>;
 
-export type ClipboardChangeCallback = (readClipboard: ReadClipboard) => void;
+export type ClipboardChangeCallback = (readClipboard: ReadClipboard, change: ClipboardChange) => void;
 
 export interface ClipboardChangeOptions {
   /**
    * default value: `true`
-   * @descCN 自动保存图片到`filePath`
-   * @descEN auto save images to `filePath`
+   * @descCN 在结果中包含已保存的图片，图片的保存位置由`startWatch`的`imageDir`决定
+   * @descEN Include the saved image in the result, where images are saved is set by `imageDir` of `startWatch`
    */
   imageAutoSave?: boolean;
   /**
-   * @descCN 保存文件和图片的可选路径，如果未提供则使用默认路径，使用`getFilePath()`获取默认路径位置
-   * @descEN Optional path to save the file and image, if not provided, the default path will be used, use `getFilePath()` to get the default path
+   * @deprecated use `imageDir` of `startWatch`
    */
   filePath?: string;
+  /**
+   * default value: `false`
+   * @descCN 开始监听后立即以最近一次变化调用回调，例如在页面刷新之后
+   * @descEN Call the callback with the latest change right away, e.g. after a reload
+   */
+  replay?: boolean;
   /**
    * @descCN 读取剪贴板之前运行的钩子函数
    * @descEN A hook function that runs before reading the clipboard
@@ -126,20 +497,28 @@ export interface ClipboardChangeOptions {
 /**
  * Start listening for clipboard changes
  *
+ * Every change event carries a `ClipboardSnapshot` of the clipboard contents. Calling it again from
+ * the same window does nothing, the watcher is released when the window is closed.
+ *
+ * @param options - Optional formats to capture and where to save the image
+ * @param filter - Optional formats and text sizes of the changes to report, defaults to every change
+ *
  * @example
  * ```
  * import { startWatch } from 'tauri-plugin-clipboard-next-api';
  *
- * await startWatch();
+ * await startWatch({ formats: ["text", "image"] }, { formats: ["image"] });
  * ```
  */
-export const startWatch = () => {
-  return invoke<void>(COMMANDS.START_WATCH)
+export const startWatch = (options?: SnapshotOptions, filter?: ChangeFilter) => {
+  return invoke<void>(COMMANDS.START_WATCH, { options, filter })
 }
 
 /**
  * Stop listening for clipboard changes
  *
+ * The watcher keeps running while other windows listen.
+ *
  * @example
  * ```
  * import { stopWatch } from 'tauri-plugin-clipboard-next-api';
@@ -151,9 +530,117 @@ export const stopWatch = () => {
   return invoke<void>(COMMANDS.STOP_WATCH)
 }
 
+/**
+ * Receive clipboard changes in the current window only
+ *
+ * Unlike `startWatch` and `onClipboardChange`, the changes are not sent to other windows. The
+ * subscription is released when the window is closed.
+ *
+ * @param cb - Called with every clipboard change
+ * @param options - Optional formats, filter, debounce interval and whether to send the contents
+ *
+ * @example
+ * ```
+ * import { subscribe } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const subscription = await subscribe((change) => {
+ *   console.log('Clipboard changed:', change.text);
+ * }, { formats: ["text"] });
+ *
+ * // Later, to stop receiving changes
+ * await subscription.unsubscribe();
+ * ```
+ */
+export const subscribe = async (cb: (change: ClipboardChange) => void, options?: SubscribeOptions): Promise<Subscription> => {
+  const channel = new Channel<ClipboardChange>();
+  channel.onmessage = cb;
+
+  const id = await invoke<number>(COMMANDS.SUBSCRIBE, { channel, options });
+
+  return { id, unsubscribe: () => unsubscribe(id) };
+}
+
+/**
+ * Stop a subscription made by `subscribe` in the current window
+ *
+ * @param id - The id of the subscription
+ *
+ * @example
+ * ```
+ * import { unsubscribe } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await unsubscribe(subscription.id);
+ * ```
+ */
+export const unsubscribe = (id: number) => {
+  return invoke<void>(COMMANDS.UNSUBSCRIBE, { id })
+}
+
+/**
+ * Stop reporting clipboard changes without stopping the watcher, e.g. for a privacy mode
+ *
+ * @param resumeAfter - Optional time in milliseconds after which changes are reported again
+ *
+ * @example
+ * ```
+ * import { pauseWatch } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await pauseWatch(60_000);
+ * ```
+ */
+export const pauseWatch = (resumeAfter?: number) => {
+  return invoke<void>(COMMANDS.PAUSE_WATCH, { resumeAfter })
+}
+
+/**
+ * Report clipboard changes again after `pauseWatch`
+ *
+ * @example
+ * ```
+ * import { resumeWatch } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await resumeWatch();
+ * ```
+ */
+export const resumeWatch = () => {
+  return invoke<void>(COMMANDS.RESUME_WATCH)
+}
+
+/**
+ * Get the state of the clipboard watcher
+ *
+ * @example
+ * ```
+ * import { watchStatus } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const { running, paused, events } = await watchStatus();
+ * ```
+ */
+export const watchStatus = () => {
+  return invoke<WatchStatus>(COMMANDS.WATCH_STATUS)
+}
+
+/**
+ * Get the latest clipboard change, the contents when the watcher started before the first change and `null` while it is stopped
+ *
+ * Like the change events, it is `null` for a window whose filter does not match the change.
+ *
+ * @example
+ * ```
+ * import { getLastChange } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const change = await getLastChange();
+ * ```
+ */
+export const getLastChange = () => {
+  return invoke<ClipboardChange | null>(COMMANDS.GET_LAST_CHANGE)
+}
+
 /**
  * Check if the clipboard contains plain text
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { hasText } from 'tauri-plugin-clipboard-next-api';
@@ -161,13 +648,15 @@ export const stopWatch = () => {
  * const has = await hasText();
  * ```
  */
-export const hasText = () => {
-  return invoke<boolean>(COMMANDS.HAS_TEXT)
+export const hasText = (options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_TEXT, { options })
 }
 
 /**
  * Check if the clipboard contains rich text
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { hasRtf } from 'tauri-plugin-clipboard-next-api';
@@ -175,13 +664,15 @@ export const hasText = () => {
  * const has = await hasRtf();
  * ```
  */
-export const hasRtf = () => {
-  return invoke<boolean>(COMMANDS.HAS_RTF)
+export const hasRtf = (options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_RTF, { options })
 }
 
 /**
  * Check if the clipboard contains html
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { hasHtml } from 'tauri-plugin-clipboard-next-api';
@@ -189,13 +680,15 @@ export const hasRtf = () => {
  * const has = await hasHtml();
  * ```
  */
-export const hasHtml = () => {
-  return invoke<boolean>(COMMANDS.HAS_HTML)
+export const hasHtml = (options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_HTML, { options })
 }
 
 /**
  * Check if the clipboard contains an image
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { hasImage } from 'tauri-plugin-clipboard-next-api';
@@ -203,13 +696,15 @@ export const hasHtml = () => {
  * const has = await hasImage();
  * ```
  */
-export const hasImage = () => {
-  return invoke<boolean>(COMMANDS.HAS_IMAGE)
+export const hasImage = (options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_IMAGE, { options })
 }
 
 /**
  * Check if the clipboard contains files
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { hasFiles } from 'tauri-plugin-clipboard-next-api';
@@ -217,13 +712,32 @@ export const hasImage = () => {
  * const has = await hasFiles();
  * ```
  */
-export const hasFiles = () => {
-  return invoke<boolean>(COMMANDS.HAS_FILES)
+export const hasFiles = (options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_FILES, { options })
+}
+
+/**
+ * Check if the clipboard contains a custom format
+ *
+ * @param format - The format name, e.g. a MIME type such as `image/svg+xml`
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { hasFormat } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const has = await hasFormat('application/x-my-app-item');
+ * ```
+ */
+export const hasFormat = (format: string, options?: OperationOptions) => {
+  return invoke<boolean>(COMMANDS.HAS_FORMAT, { format, options })
 }
 
 /**
  * Read plain text from the clipboard
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { readText } from 'tauri-plugin-clipboard-next-api';
@@ -231,13 +745,15 @@ export const hasFiles = () => {
  * const text = await readText();
  * ```
  */
-export const readText = () => {
-  return invoke<string>(COMMANDS.READ_TEXT)
+export const readText = (options?: OperationOptions) => {
+  return invoke<string>(COMMANDS.READ_TEXT, { options })
 }
 
 /**
  * Read rich text from the clipboard
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { readRtf } from 'tauri-plugin-clipboard-next-api';
@@ -245,13 +761,15 @@ export const readText = () => {
  * const rtf = await readRtf();
  * ```
  */
-export const readRtf = () => {
-  return invoke<string>(COMMANDS.READ_RTF)
+export const readRtf = (options?: OperationOptions) => {
+  return invoke<string>(COMMANDS.READ_RTF, { options })
 }
 
 /**
  * Read html from the clipboard
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { readHtml } from 'tauri-plugin-clipboard-next-api';
@@ -259,14 +777,15 @@ export const readRtf = () => {
  * const html = await readHtml();
  * ```
  */
-export const readHtml = () => {
-  return invoke<string>(COMMANDS.READ_HTML)
+export const readHtml = (options?: OperationOptions) => {
+  return invoke<string>(COMMANDS.READ_HTML, { options })
 }
 
 /**
  * Read image from the clipboard
  *
  * @param savePath - Optional path to save the image file
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
  *
  * @example
  * ```
@@ -275,13 +794,15 @@ export const readHtml = () => {
  * const image = await readImage('/path/to/save');
  * ```
  */
-export const readImage = (savePath?: string) => {
-  return invoke<ReadImage>(COMMANDS.READ_IMAGE, { savePath })
+export const readImage = (savePath?: string, options?: OperationOptions) => {
+  return invoke<ReadImage>(COMMANDS.READ_IMAGE, { savePath, options })
 }
 
 /**
  * Read file paths from the clipboard
  *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
  * @example
  * ```
  * import { readFiles } from 'tauri-plugin-clipboard-next-api';
@@ -289,14 +810,55 @@ export const readImage = (savePath?: string) => {
  * const files = await readFiles();
  * ```
  */
-export const readFiles = () => {
-  return invoke<ReadFiles>(COMMANDS.READ_FILES)
+export const readFiles = (options?: OperationOptions) => {
+  return invoke<ReadFiles>(COMMANDS.READ_FILES, { options })
+}
+
+/**
+ * Read the raw bytes of a custom format from the clipboard
+ *
+ * @param format - The format name, e.g. a MIME type such as `image/svg+xml`
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { readFormat } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const buffer = await readFormat('image/svg+xml');
+ * const svg = new TextDecoder().decode(buffer);
+ * ```
+ */
+export const readFormat = (format: string, options?: OperationOptions) => {
+  return invoke<ArrayBuffer>(COMMANDS.READ_FORMAT, { format, options })
+}
+
+/**
+ * Read every requested format from the clipboard at once
+ *
+ * The contents are read in a single clipboard operation, so they cannot change in between.
+ *
+ * @param options - Optional formats to read and where to save the image
+ * @param operation - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { readAll } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const snapshot = await readAll({ formats: ['text', 'html', 'image'] });
+ * if (snapshot.html !== null) {
+ *   console.log(snapshot.html);
+ * }
+ * ```
+ */
+export const readAll = (options?: SnapshotOptions, operation?: OperationOptions) => {
+  return invoke<ClipboardSnapshot>(COMMANDS.READ_ALL, { options, operation })
 }
 
 /**
  * Write plain text to the clipboard
  *
  * @param content - The text content to write
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
  *
  * @example
  * ```
@@ -305,14 +867,15 @@ export const readFiles = () => {
  * await writeText('Hello, World!');
  * ```
  */
-export const writeText = (content: string) => {
-  return invoke<void>(COMMANDS.WRITE_TEXT, { content })
+export const writeText = (content: string, options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.WRITE_TEXT, { content, options })
 }
 
 /**
  * Write rich text to the clipboard
  *
  * @param content - The RTF content to write
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
  *
  * @example
  * ```
@@ -321,14 +884,15 @@ export const writeText = (content: string) => {
  * await writeRtf("\x1b[1m\x1b[4m\x1b[31mHello, World!\x1b[0m");
  * ```
  */
-export const writeRtf = (content: string) => {
-  return invoke<void>(COMMANDS.WRITE_RTF, { content })
+export const writeRtf = (content: string, options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.WRITE_RTF, { content, options })
 }
 
 /**
  * Write html content to the clipboard
  *
  * @param content - The HTML content to write
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
  *
  * @example
  * ```
@@ -337,14 +901,15 @@ export const writeRtf = (content: string) => {
  * await writeHtml('<p>Hello</p>');
  * ```
  */
-export const writeHtml = (content: string) => {
-  return invoke<void>(COMMANDS.WRITE_HTML, { content })
+export const writeHtml = (content: string, options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.WRITE_HTML, { content, options })
 }
 
 /**
  * Write an image to the clipboard from a file path
  *
  * @param imagePath - Path to the image file
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
  *
  * @example
  * ```
@@ -353,14 +918,15 @@ export const writeHtml = (content: string) => {
  * await writeImage('/path/to/image.png');
  * ```
  */
-export const writeImage = (imagePath: string) => {
-  return invoke<void>(COMMANDS.WRITE_IMAGE, { imagePath })
+export const writeImage = (imagePath: string, options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.WRITE_IMAGE, { imagePath, options })
 }
 
 /**
  * Write file paths to the clipboard
  *
  * @param filesPath - Array of file paths to write
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
  *
  * @example
  * ```
@@ -369,13 +935,77 @@ export const writeImage = (imagePath: string) => {
  * await writeFiles(['/path/to/file1.txt', '/path/to/file2.txt']);
  * ```
  */
-export const writeFiles = (filesPath: string[]) => {
-  return invoke<void>(COMMANDS.WRITE_FILES, { filesPath })
+export const writeFiles = (filesPath: string[], options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.WRITE_FILES, { filesPath, options })
+}
+
+/**
+ * Write the raw bytes of a custom format to the clipboard
+ *
+ * @param format - The format name, e.g. a MIME type such as `image/svg+xml`
+ * @param data - The bytes to write, sent as is
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { writeFormat } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await writeFormat('image/svg+xml', new TextEncoder().encode('<svg xmlns="http://www.w3.org/2000/svg"/>'));
+ * ```
+ */
+export const writeFormat = (
+  format: string,
+  data: Uint8Array | ArrayBuffer,
+  options?: OperationOptions,
+) => {
+  const headers: Record<string, string> = { "clipboard-format": format }
+
+  if (options) {
+    headers["clipboard-options"] = JSON.stringify(options)
+  }
+
+  return invoke<void>(COMMANDS.WRITE_FORMAT, data, { headers })
+}
+
+/**
+ * Write several representations of the same content to the clipboard at once
+ *
+ * @param representations - The text, html, rtf, image, files and custom format contents to write
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { write } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await write([
+ *   { type: 'html', content: '<b>Hello</b>' },
+ *   { type: 'text', content: 'Hello' },
+ *   { type: 'image', path: '/path/to/image.png' },
+ * ]);
+ * ```
+ */
+export const write = (representations: Representation[], options?: OperationOptions) => {
+  // typed arrays would be serialized as objects
+  const toArray = (bytes: Uint8Array | ArrayBuffer | number[]) => {
+    return Array.from(bytes instanceof ArrayBuffer ? new Uint8Array(bytes) : bytes)
+  }
+
+  representations = representations.map((representation) => {
+    if ("bytes" in representation && representation.bytes) {
+      return { ...representation, bytes: toArray(representation.bytes) } as Representation
+    }
+
+    return representation
+  })
+
+  return invoke<void>(COMMANDS.WRITE, { representations, options })
 }
 
 /**
  * Clear the clipboard contents
  *
+ * @param options - Optional timeout, retry policy and echo suppression, defaults to the plugin config
+ *
  * @example
  * ```
  * import { clear } from 'tauri-plugin-clipboard-next-api';
@@ -383,8 +1013,63 @@ export const writeFiles = (filesPath: string[]) => {
  * await clear();
  * ```
  */
-export const clear = () => {
-  return invoke<void>(COMMANDS.CLEAR)
+export const clear = (options?: OperationOptions) => {
+  return invoke<void>(COMMANDS.CLEAR, { options })
+}
+
+/**
+ * List every format the clipboard contains
+ *
+ * @param sizes - Read every format except images to report its size in bytes, defaults to `false`
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { availableFormats } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const formats = await availableFormats(true);
+ * const svg = formats.find((info) => info.name === 'image/svg+xml');
+ * ```
+ */
+export const availableFormats = (sizes?: boolean, options?: OperationOptions) => {
+  return invoke<FormatInfo[]>(COMMANDS.AVAILABLE_FORMATS, { sizes, options })
+}
+
+/**
+ * Get the number of clipboard changes seen so far
+ *
+ * The count only increases, comparing it with a previous value tells whether the clipboard changed.
+ * Without a running watcher the contents are compared on every call instead.
+ *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { changeCount } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const count = await changeCount();
+ * ```
+ */
+export const changeCount = (options?: OperationOptions) => {
+  return invoke<number>(COMMANDS.CHANGE_COUNT, { options })
+}
+
+/**
+ * Get a hash of the clipboard contents across all formats, equal contents give the same hash
+ *
+ * The hash does not change between app runs or plugin builds, so it can be stored.
+ *
+ * @param options - Optional timeout and retry policy, defaults to the plugin config
+ *
+ * @example
+ * ```
+ * import { fingerprint } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const hash = await fingerprint();
+ * ```
+ */
+export const fingerprint = (options?: OperationOptions) => {
+  return invoke<string>(COMMANDS.FINGERPRINT, { options })
 }
 
 /**
@@ -401,9 +1086,27 @@ export const getFilePath = () => {
   return invoke<string>(COMMANDS.GET_FILE_PATH)
 }
 
+/**
+ * Get the availability of the clipboard, reconnecting if it was unavailable
+ *
+ * Answers right away, the clipboard is reported unavailable while an operation hangs past its timeout.
+ *
+ * @example
+ * ```
+ * import { status } from 'tauri-plugin-clipboard-next-api';
+ *
+ * const { available, error } = await status();
+ * ```
+ */
+export const status = () => {
+  return invoke<ClipboardStatus>(COMMANDS.STATUS)
+}
+
 /**
  * Read all available content from the clipboard
  *
+ * The contents are read in a single call to `readAll`, so they cannot change in between.
+ *
  * @returns An object containing all available clipboard formats and their content
  *
  * @example
@@ -417,78 +1120,126 @@ export const getFilePath = () => {
  * ```
  */
 export const readClipboard = async (imageAutoSave?: boolean, filePath?: string) => {
+  const snapshot = await readAll({
+    formats: imageAutoSave ? undefined : ["text", "rtf", "html", "files"],
+    saveImage: !!imageAutoSave,
+    imageDir: filePath,
+  });
+
+  return fromSnapshot(snapshot, !!imageAutoSave);
+}
+
+/**
+ * Listen for clipboard changes
+ *
+ * @param cb - Callback function to be called when clipboard changes
+ * @param options - Optional configuration for the listener
+ *
+ * @example
+ * ```
+ * import { startWatch, onClipboardChange } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await startWatch();
+ *
+ * const unlisten = await onClipboardChange((clipboard) => {
+ *   console.log('Clipboard changed:', clipboard);
+ * });
+ *
+ * // Later, to stop listening
+ * unlisten();
+ * ```
+ */
+export const onClipboardChange = async (cb: ClipboardChangeCallback, options?: ClipboardChangeOptions) => {
+  const { before, imageAutoSave = true, replay = false } = options || {}
+
+  const handleChange = (change: ClipboardChange) => {
+    before?.();
+
+    cb(fromSnapshot(change, imageAutoSave), change);
+  }
+
+  // changes are emitted to each window with its own filter, listening to every window would receive them all
+  const unlisten = await getCurrentWebviewWindow().listen<ClipboardChange>(EVENTS.CLIPBOARD_CHANGE, ({ payload }) => {
+    handleChange(payload);
+  })
+
+  if (replay) {
+    const change = await getLastChange();
+
+    if (change) {
+      handleChange(change);
+    }
+  }
+
+  return unlisten;
+}
+
+/**
+ * Listen for the clipboard becoming empty, e.g. after `clear`
+ *
+ * @param cb - Callback function to be called when the clipboard is cleared
+ *
+ * @example
+ * ```
+ * import { startWatch, onClipboardCleared } from 'tauri-plugin-clipboard-next-api';
+ *
+ * await startWatch();
+ *
+ * const unlisten = await onClipboardCleared(() => {
+ *   console.log('Clipboard cleared');
+ * });
+ * ```
+ */
+export const onClipboardCleared = (cb: (change: ClipboardChange) => void) => {
+  return getCurrentWebviewWindow().listen<ClipboardChange>(EVENTS.CLIPBOARD_CLEARED, ({ payload }) => {
+    cb(payload);
+  })
+}
+
+const fromSnapshot = (snapshot: ClipboardSnapshot, imageAutoSave: boolean) => {
   const readClipboard: ReadClipboard = {};
 
-  if (await hasText()) {
-    const content = await readText();
+  if (snapshot.text !== null) {
     readClipboard.text = {
       format: "text",
-      value: content
+      value: snapshot.text
     }
   }
 
-  if (await hasRtf()) {
-    const content = await readRtf();
+  if (snapshot.rtf !== null) {
     readClipboard.rtf = {
       format: "rtf",
-      value: content
+      value: snapshot.rtf
     }
   }
 
-  if (await hasHtml()) {
-    const content = await readHtml();
+  if (snapshot.html !== null) {
     readClipboard.html = {
       format: "html",
-      value: content
+      value: snapshot.html
     }
   }
 
-  if (imageAutoSave && await hasImage()) {
-    const content = await readImage(filePath);
+  const image = snapshot.image;
+
+  if (imageAutoSave && image?.path != null) {
     readClipboard.image = {
       format: "image",
-      value: content
+      value: {
+        path: image.path,
+        width: image.width,
+        height: image.height,
+        size: image.size ?? 0
+      }
     }
   }
 
-  if (await hasFiles()) {
-    const content = await readFiles();
+  if (snapshot.files !== null) {
     readClipboard.files = {
       format: "files",
-      value: content
+      value: snapshot.files
     }
   }
 
   return readClipboard;
-}
-
-/**
- * Listen for clipboard changes
- *
- * @param cb - Callback function to be called when clipboard changes
- * @param options - Optional configuration for the listener
- *
- * @example
- * ```
- * import { startWatch, onClipboardChange } from 'tauri-plugin-clipboard-next-api';
- *
- * await startWatch();
- *
- * const unlisten = await onClipboardChange((clipboard) => {
- *   console.log('Clipboard changed:', clipboard);
- * });
- *
- * // Later, to stop listening
- * unlisten();
- * ```
- */
-export const onClipboardChange = (cb: ClipboardChangeCallback, options?: ClipboardChangeOptions) => {
-  const { before, imageAutoSave = true, filePath } = options || {}
-
-  return listen(EVENTS.CLIPBOARD_CHANGE, async () => {
-    before?.();
-
-    const read = await readClipboard(imageAutoSave, filePath);
-    cb(read);
-  })
 }
\ No newline at end of file
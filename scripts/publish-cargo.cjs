@@ -1,9 +1,14 @@
 const { execSync } = require('child_process');
 
+// The core crate must be published before the plugin that depends on it
+const crates = ['clipboard-next-core', 'tauri-plugin-clipboard-next'];
+
 console.log('Publishing to crates.io...');
 
 try {
-  execSync('cargo publish', { stdio: 'inherit' });
+  for (const crate of crates) {
+    execSync(`cargo publish -p ${crate}`, { stdio: 'inherit' });
+  }
   console.log('Successfully published to crates.io!');
 } catch (error) {
   console.error('Failed to publish to crates.io:', error.message);
@@ -9,13 +9,18 @@ const version = packageJson.version;
 
 console.log(`Syncing version ${version} from package.json to Cargo.toml...`);
 
-// Update Cargo.toml
-const cargoTomlPath = path.join(__dirname, '..', 'Cargo.toml');
-let cargoToml = fs.readFileSync(cargoTomlPath, 'utf8');
-cargoToml = cargoToml.replace(/^version = ".*"/m, `version = "${version}"`);
-fs.writeFileSync(cargoTomlPath, cargoToml, 'utf8');
+const cargoTomlPaths = ['Cargo.toml', 'crates/clipboard-next-core/Cargo.toml'];
 
-// Stage Cargo.toml so release-it includes it in the commit
-execSync('git add Cargo.toml', { stdio: 'inherit' });
+for (const cargoTomlPath of cargoTomlPaths) {
+  // Update Cargo.toml
+  const fullPath = path.join(__dirname, '..', cargoTomlPath);
+  let cargoToml = fs.readFileSync(fullPath, 'utf8');
+  cargoToml = cargoToml.replace(/^version = ".*"/m, `version = "${version}"`);
+  cargoToml = cargoToml.replace(/^(clipboard-next-core = \{ version = )".*?"/m, `$1"${version}"`);
+  fs.writeFileSync(fullPath, cargoToml, 'utf8');
 
-console.log(`Cargo.toml updated to version ${version}`);
+  // Stage Cargo.toml so release-it includes it in the commit
+  execSync(`git add ${cargoTomlPath}`, { stdio: 'inherit' });
+
+  console.log(`${cargoTomlPath} updated to version ${version}`);
+}
@@ -0,0 +1,700 @@
+use crate::subscription::{
+    ChangeCallback, ChannelSubscription, SubscribeOptions, Subscribers, Subscription,
+};
+use crate::writer::Writer;
+use crate::{constants, utils, Result};
+use clipboard_next_core::{
+    debounce, ChangeFilter, ClipboardChange, ClipboardNextManager, ClipboardSnapshot,
+    ClipboardStatus, ClipboardWorker, ContentFormat, FormatInfo, OperationOptions, ReadFiles,
+    ReadImage, Representation, SnapshotOptions, WatchStatus,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::async_runtime::{self, Mutex};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime};
+
+#[cfg(mobile)]
+use tauri::plugin::PluginHandle;
+
+/// Access to the clipboard-next APIs.
+///
+/// Clipboard operations run on a dedicated worker thread, the async methods never block the
+/// async runtime and fail with a timeout error if the clipboard does not respond.
+pub struct ClipboardNext<R: Runtime> {
+    pub(crate) worker: ClipboardWorker,
+    pub(crate) watch_owners: Mutex<HashMap<String, ChangeFilter>>,
+    pub(crate) subscribers: Arc<Subscribers>,
+    /// Filters of the owners other than subscriptions, by window label
+    pub(crate) broadcast: Arc<std::sync::Mutex<HashMap<String, ChangeFilter>>>,
+    pub(crate) channels: Mutex<HashMap<u64, ChannelSubscription<R>>>,
+    pub(crate) app_handle: AppHandle<R>,
+    #[cfg(mobile)]
+    pub(crate) _handle: PluginHandle<R>,
+}
+
+impl<R: Runtime> ClipboardNext<R> {
+    /// Emit a [`ClipboardChange`](clipboard_next_core::ClipboardChange) with every clipboard change on behalf of `owner`, e.g. a window label
+    ///
+    /// The event is sent to every window holding the watcher whose `filter` matches the change, and
+    /// to the other windows if `autoStartWatch` holds it and its filter matches. A
+    /// `clipboard_cleared` event is emitted to the same windows when the clipboard becomes empty. The
+    /// watcher runs while at least one owner holds it, starting it again for the same owner only
+    /// replaces its filter. The options of the first owner are kept until every owner has stopped.
+    /// Images are saved to [`ClipboardNext::get_file_path`] unless `options` say otherwise.
+    pub async fn start_watch(
+        &self,
+        app_handle: AppHandle<R>,
+        owner: &str,
+        options: Option<SnapshotOptions>,
+        filter: Option<ChangeFilter>,
+    ) -> Result<()> {
+        let mut watch_owners = self.watch_owners.lock().await;
+
+        if watch_owners.is_empty() {
+            let mut options = options.unwrap_or_default();
+
+            if options.save_image && options.image_dir.is_none() {
+                options.image_dir = Some(self.get_file_path(app_handle.clone())?);
+            }
+
+            let worker = self.worker.clone();
+            let subscribers = self.subscribers.clone();
+            let broadcast = self.broadcast.clone();
+
+            let started = async_runtime::spawn_blocking(move || {
+                worker.start_watch(options, move |change| {
+                    subscribers.notify(&change);
+
+                    let owners = broadcast.lock().unwrap().clone();
+                    emit_change(&app_handle, &owners, &change);
+                })
+            })
+            .await?;
+
+            match started {
+                Ok(()) | Err(clipboard_next_core::Error::WatcherAlreadyRunning) => {}
+                Err(err @ clipboard_next_core::Error::Timeout(_)) => {
+                    // the start may still run after the caller gave up, the stop queued behind it
+                    // leaves no watcher running without an owner
+                    let worker = self.worker.clone();
+                    drop(self.worker.submit(move |_| worker.stop_watch()));
+
+                    return Err(err.into());
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        watch_owners.insert(owner.to_string(), filter.unwrap_or_default());
+        self.update_broadcast(&watch_owners);
+
+        Ok(())
+    }
+
+    /// Release the watcher held by `owner`, it is stopped once no owner holds it
+    pub async fn stop_watch(&self, owner: &str) -> Result<()> {
+        let mut watch_owners = self.watch_owners.lock().await;
+
+        if !watch_owners.contains_key(owner) {
+            return Ok(());
+        }
+
+        if watch_owners.len() == 1 {
+            self.worker.stop_watch()?;
+        }
+
+        watch_owners.remove(owner);
+        self.update_broadcast(&watch_owners);
+
+        Ok(())
+    }
+
+    /// Subscriptions only receive changes through their callbacks, emitting would leak the
+    /// clipboard contents to every webview
+    fn update_broadcast(&self, watch_owners: &HashMap<String, ChangeFilter>) {
+        *self.broadcast.lock().unwrap() = watch_owners
+            .iter()
+            .filter(|(owner, _)| !owner.starts_with(constants::plugin::SUBSCRIPTION_OWNER))
+            .map(|(owner, filter)| (owner.clone(), filter.clone()))
+            .collect();
+    }
+
+    /// Call `callback` with every clipboard change matching `filter` until the returned [`Subscription`] is dropped
+    ///
+    /// The subscription holds the watcher like a window does, without depending on a webview.
+    /// `options` only apply if the watcher is not running yet, otherwise the changes are captured
+    /// with the options it was started with. `callback` runs on the clipboard worker thread and must
+    /// not wait for clipboard operations.
+    ///
+    /// ```rust,ignore
+    /// let subscription = app.clipboard_next().subscribe(None, None, |change| {
+    ///     if let Some(text) = &change.snapshot.text {
+    ///         index(text);
+    ///     }
+    /// }).await?;
+    /// ```
+    pub async fn subscribe<F>(
+        &self,
+        options: Option<SnapshotOptions>,
+        filter: Option<ChangeFilter>,
+        callback: F,
+    ) -> Result<Subscription<R>>
+    where
+        F: Fn(&ClipboardChange) + Send + Sync + 'static,
+    {
+        let filter = filter.unwrap_or_default();
+
+        let callback: ChangeCallback = Arc::new(move |change: &ClipboardChange| {
+            if filter.matches(&change.snapshot) {
+                callback(change);
+            }
+        });
+        let id = self.subscribers.insert(callback);
+
+        if let Err(err) = self
+            .start_watch(
+                self.app_handle.clone(),
+                &subscription_owner(id),
+                options,
+                None,
+            )
+            .await
+        {
+            self.subscribers.remove(id);
+            return Err(err);
+        }
+
+        Ok(Subscription {
+            id,
+            app_handle: self.app_handle.clone(),
+        })
+    }
+
+    /// Remove the subscription with the given id and release its watcher
+    pub async fn unsubscribe(&self, id: u64) -> Result<()> {
+        if self.subscribers.remove(id) {
+            self.stop_watch(&subscription_owner(id)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send clipboard changes to `channel` of the window `label` only, trimmed to `options`
+    ///
+    /// With [`SubscribeOptions::replay`] the [`backlog`](ClipboardNext::backlog) is sent first. The subscription lasts until [`ClipboardNext::unsubscribe_channel`] or until the window is
+    /// destroyed, the returned id identifies it.
+    pub async fn subscribe_channel(
+        &self,
+        label: &str,
+        channel: Channel<ClipboardChange>,
+        options: Option<SubscribeOptions>,
+    ) -> Result<u64> {
+        let mut options = options.unwrap_or_default();
+        let filter = options.filter.take();
+
+        if options.replay {
+            let replay_filter = filter.clone().unwrap_or_default();
+
+            for mut change in self.backlog() {
+                if replay_filter.matches(&change.snapshot) {
+                    options.apply(&mut change);
+                    let _ = channel.send(change);
+                }
+            }
+        }
+
+        let mut send: Box<dyn FnMut(ClipboardChange) + Send> = Box::new(move |change| {
+            let _ = channel.send(change);
+        });
+
+        if options.debounce > 0 {
+            send = debounce(send, Duration::from_millis(options.debounce), None);
+        }
+
+        let send = std::sync::Mutex::new(send);
+
+        // held while subscribing so that unsubscribing cannot come before the channel is known
+        let mut channels = self.channels.lock().await;
+
+        let subscription = self
+            .subscribe(None, filter, move |change| {
+                let mut change = change.clone();
+                options.apply(&mut change);
+
+                (send.lock().unwrap())(change);
+            })
+            .await?;
+        let id = subscription.id();
+
+        channels.insert(
+            id,
+            ChannelSubscription {
+                label: label.to_string(),
+                _subscription: subscription,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Remove the subscription with the given id if it belongs to the window `label`
+    pub async fn unsubscribe_channel(&self, label: &str, id: u64) -> Result<()> {
+        let mut channels = self.channels.lock().await;
+
+        if channels
+            .get(&id)
+            .is_some_and(|channel| channel.label == label)
+        {
+            channels.remove(&id);
+            self.unsubscribe(id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove every subscription of the window `label`, called when the window is destroyed
+    pub(crate) async fn unsubscribe_window(&self, label: &str) -> Result<()> {
+        let ids: Vec<u64> = self
+            .channels
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, channel)| channel.label == label)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in ids {
+            self.unsubscribe_channel(label, id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Stop reporting changes without releasing the watcher, e.g. for a privacy mode
+    ///
+    /// Reporting resumes after `resume_after` milliseconds if given, otherwise on [`ClipboardNext::resume_watch`].
+    pub fn pause_watch(&self, resume_after: Option<u64>) -> Result<()> {
+        Ok(self
+            .worker
+            .pause_watch(resume_after.map(Duration::from_millis))?)
+    }
+
+    pub fn resume_watch(&self) -> Result<()> {
+        Ok(self.worker.resume_watch()?)
+    }
+
+    pub fn watch_status(&self) -> WatchStatus {
+        self.worker.watch_status()
+    }
+
+    /// The latest change as the window `label` receives it, `None` if the change events would not
+    /// reach it, see [`ClipboardNext::start_watch`]
+    pub fn last_change_for(&self, label: &str) -> Option<ClipboardChange> {
+        let change = self.last_change()?;
+        let owners = self.broadcast.lock().unwrap();
+        let filter = owners
+            .get(label)
+            .or_else(|| owners.get(constants::plugin::WATCH_OWNER))?;
+
+        filter.matches(&change.snapshot).then_some(change)
+    }
+
+    /// The latest clipboard change, e.g. for a window that opened after it, `None` while the
+    /// watcher is stopped
+    pub fn last_change(&self) -> Option<ClipboardChange> {
+        self.worker.last_change()
+    }
+
+    /// The recent clipboard changes, oldest first, up to the `backlog` config
+    pub fn backlog(&self) -> Vec<ClipboardChange> {
+        self.worker.backlog()
+    }
+
+    /// Stop the watcher regardless of its owners, called when the app exits
+    pub(crate) fn stop_all_watch(&self) {
+        if let Ok(mut watch_owners) = self.watch_owners.try_lock() {
+            watch_owners.clear();
+        }
+
+        self.broadcast.lock().unwrap().clear();
+
+        // bypasses the queue, the worker may be stuck on an unresponsive clipboard
+        let _ = self.worker.stop_watch();
+    }
+
+    pub async fn has_text(&self, options: Option<OperationOptions>) -> Result<bool> {
+        self.run(options, |manager| manager.has(ContentFormat::Text))
+            .await
+    }
+
+    pub async fn has_rtf(&self, options: Option<OperationOptions>) -> Result<bool> {
+        self.run(options, |manager| manager.has(ContentFormat::Rtf))
+            .await
+    }
+
+    pub async fn has_html(&self, options: Option<OperationOptions>) -> Result<bool> {
+        self.run(options, |manager| manager.has(ContentFormat::Html))
+            .await
+    }
+
+    pub async fn has_image(&self, options: Option<OperationOptions>) -> Result<bool> {
+        self.run(options, |manager| manager.has(ContentFormat::Image))
+            .await
+    }
+
+    pub async fn has_files(&self, options: Option<OperationOptions>) -> Result<bool> {
+        self.run(options, |manager| manager.has(ContentFormat::Files))
+            .await
+    }
+
+    /// Check if the clipboard contains a custom format, e.g. a MIME type
+    pub async fn has_format(
+        &self,
+        format: String,
+        options: Option<OperationOptions>,
+    ) -> Result<bool> {
+        self.run(options, move |manager| manager.has_format(&format))
+            .await
+    }
+
+    pub async fn read_text(&self, options: Option<OperationOptions>) -> Result<String> {
+        self.run(options, |manager| manager.read_text()).await
+    }
+
+    pub async fn read_rtf(&self, options: Option<OperationOptions>) -> Result<String> {
+        self.run(options, |manager| manager.read_rtf()).await
+    }
+
+    pub async fn read_html(&self, options: Option<OperationOptions>) -> Result<String> {
+        self.run(options, |manager| manager.read_html()).await
+    }
+
+    pub async fn read_image(
+        &self,
+        app_handle: AppHandle<R>,
+        save_path: Option<PathBuf>,
+        options: Option<OperationOptions>,
+    ) -> Result<ReadImage> {
+        let file_path = match save_path {
+            None => self.get_file_path(app_handle)?,
+            Some(path) => path,
+        };
+
+        self.run(options, move |manager| {
+            manager.read_image(file_path.clone())
+        })
+        .await
+    }
+
+    pub async fn read_files(&self, options: Option<OperationOptions>) -> Result<ReadFiles> {
+        self.run(options, |manager| manager.read_files()).await
+    }
+
+    /// Read the raw bytes of a custom format
+    pub async fn read_format(
+        &self,
+        format: String,
+        options: Option<OperationOptions>,
+    ) -> Result<Vec<u8>> {
+        self.run(options, move |manager| manager.read_format(&format))
+            .await
+    }
+
+    pub async fn write_text(
+        &self,
+        content: String,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| manager.write_text(content.clone()))
+            .await
+    }
+
+    pub async fn write_rtf(
+        &self,
+        content: String,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| manager.write_rtf(content.clone()))
+            .await
+    }
+
+    pub async fn write_html(
+        &self,
+        content: String,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| manager.write_html(content.clone()))
+            .await
+    }
+
+    pub async fn write_image(
+        &self,
+        image_path: String,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| {
+            manager.write_image(image_path.clone())
+        })
+        .await
+    }
+
+    pub async fn write_files(
+        &self,
+        files_path: Vec<String>,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| {
+            manager.write_files(files_path.clone())
+        })
+        .await
+    }
+
+    /// Replace the clipboard contents with the raw bytes of a custom format
+    pub async fn write_format(
+        &self,
+        format: String,
+        buffer: Vec<u8>,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| {
+            manager.write_format(format.clone(), buffer.clone())
+        })
+        .await
+    }
+
+    /// Replace the clipboard contents with every representation at once
+    pub async fn write(
+        &self,
+        representations: Vec<Representation>,
+        options: Option<OperationOptions>,
+    ) -> Result<()> {
+        self.run(options, move |manager| {
+            manager.write(representations.clone())
+        })
+        .await
+    }
+
+    /// Start a write putting several representations on the clipboard at once
+    pub fn writer(&self) -> Writer<'_, R> {
+        Writer::new(self)
+    }
+
+    pub async fn clear(&self, options: Option<OperationOptions>) -> Result<()> {
+        self.run(options, |manager| manager.clear()).await
+    }
+
+    /// Read every requested format in a single clipboard operation
+    ///
+    /// Images are saved to [`ClipboardNext::get_file_path`] unless `options` say otherwise.
+    pub async fn read_all(
+        &self,
+        app_handle: AppHandle<R>,
+        options: Option<SnapshotOptions>,
+        operation: Option<OperationOptions>,
+    ) -> Result<ClipboardSnapshot> {
+        let mut options = options.unwrap_or_default();
+
+        if options.save_image && options.image_dir.is_none() {
+            options.image_dir = Some(self.get_file_path(app_handle)?);
+        }
+
+        self.run(operation, move |manager| manager.snapshot(&options))
+            .await
+    }
+
+    /// List the formats the clipboard contains, including custom ones, see
+    /// [`ClipboardNextManager::available_formats`]
+    pub async fn available_formats(
+        &self,
+        sizes: bool,
+        options: Option<OperationOptions>,
+    ) -> Result<Vec<FormatInfo>> {
+        self.run(options, move |manager| manager.available_formats(sizes))
+            .await
+    }
+
+    /// Number of clipboard changes seen so far, see [`ClipboardNextManager::change_count`]
+    pub async fn change_count(&self, options: Option<OperationOptions>) -> Result<u64> {
+        self.run(options, |manager| manager.change_count()).await
+    }
+
+    /// Hash of the clipboard contents, to tell whether they changed without reading them
+    pub async fn fingerprint(&self, options: Option<OperationOptions>) -> Result<String> {
+        self.run(options, |manager| manager.fingerprint()).await
+    }
+
+    /// Status of the clipboard, answered right away even while an operation hangs
+    pub async fn status(&self) -> Result<ClipboardStatus> {
+        let worker = self.worker.clone();
+
+        Ok(async_runtime::spawn_blocking(move || worker.status()).await?)
+    }
+
+    pub fn get_file_path(&self, app_handle: AppHandle<R>) -> Result<PathBuf> {
+        match &self.worker.manager().config().storage_dir {
+            Some(storage_dir) => {
+                if !storage_dir.exists() {
+                    fs::create_dir_all(storage_dir)?;
+                }
+
+                Ok(storage_dir.clone())
+            }
+            None => utils::get_file_path(app_handle),
+        }
+    }
+
+    /// Run `f` on the clipboard worker and wait for it without blocking the async runtime
+    ///
+    /// Unset options fall back to the plugin config. Dropping the returned future, or timing out,
+    /// cancels the operation if it has not started yet.
+    async fn run<T, F>(&self, options: Option<OperationOptions>, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnMut(&ClipboardNextManager) -> clipboard_next_core::Result<T> + Send + 'static,
+    {
+        let pending = self.worker.submit_with(&options.unwrap_or_default(), f);
+        let _cancel_guard = pending.cancel_guard();
+
+        match pending.timeout() {
+            Some(timeout) => Ok(tokio::time::timeout(timeout, pending)
+                .await
+                .map_err(|_| clipboard_next_core::Error::Timeout(timeout))??),
+            None => Ok(pending.await?),
+        }
+    }
+}
+
+/// Owner of the watcher held by a subscription
+fn subscription_owner(id: u64) -> String {
+    format!("{}:{}", constants::plugin::SUBSCRIPTION_OWNER, id)
+}
+
+/// Emit `change` to every window holding the watcher whose filter matches it
+///
+/// Windows that do not hold the watcher themselves get it with the filter of `autoStartWatch`, if
+/// it holds the watcher.
+fn emit_change<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    owners: &HashMap<String, ChangeFilter>,
+    change: &ClipboardChange,
+) {
+    let auto_start = owners.get(constants::plugin::WATCH_OWNER);
+
+    for label in app_handle.webview_windows().into_keys() {
+        let Some(filter) = owners.get(&label).or(auto_start) else {
+            continue;
+        };
+
+        // the cleared event carries no content, it is not filtered
+        if change.is_cleared() {
+            let _ = app_handle.emit_to(
+                EventTarget::webview_window(&label),
+                constants::event::CLIPBOARD_CLEARED,
+                change,
+            );
+        }
+
+        if filter.matches(&change.snapshot) {
+            let _ = app_handle.emit_to(
+                EventTarget::webview_window(&label),
+                constants::event::CLIPBOARD_CHANGE,
+                change,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Builder, ChangeHandler, ClipboardBackend, ClipboardContent, ClipboardNextExt,
+        MemoryBackend, WatchHandle,
+    };
+    use std::sync::Condvar;
+    use tauri::async_runtime::block_on;
+    use tauri::test::{mock_builder, mock_context, noop_assets};
+
+    /// [`MemoryBackend`] whose reads block until released
+    #[derive(Clone)]
+    struct BlockingBackend {
+        inner: MemoryBackend,
+        blocked: Arc<(std::sync::Mutex<bool>, Condvar)>,
+    }
+
+    impl BlockingBackend {
+        fn new() -> Self {
+            Self {
+                inner: MemoryBackend::new(),
+                blocked: Arc::new((std::sync::Mutex::new(true), Condvar::new())),
+            }
+        }
+
+        fn release(&self) {
+            *self.blocked.0.lock().unwrap() = false;
+            self.blocked.1.notify_all();
+        }
+    }
+
+    impl ClipboardBackend for BlockingBackend {
+        fn has(&self, format: ContentFormat) -> clipboard_next_core::Result<bool> {
+            self.inner.has(format)
+        }
+
+        fn get(
+            &self,
+            formats: &[ContentFormat],
+        ) -> clipboard_next_core::Result<Vec<ClipboardContent>> {
+            let (blocked, released) = &*self.blocked;
+            let mut blocked = blocked.lock().unwrap();
+
+            while *blocked {
+                blocked = released.wait(blocked).unwrap();
+            }
+
+            self.inner.get(formats)
+        }
+
+        fn set(&self, contents: Vec<ClipboardContent>) -> clipboard_next_core::Result<()> {
+            self.inner.set(contents)
+        }
+
+        fn clear(&self) -> clipboard_next_core::Result<()> {
+            self.inner.clear()
+        }
+
+        fn watch(&self, handler: ChangeHandler) -> clipboard_next_core::Result<WatchHandle> {
+            self.inner.watch(handler)
+        }
+    }
+
+    #[test]
+    fn start_watch_that_times_out_leaves_no_watcher_running() {
+        let backend = BlockingBackend::new();
+        let app = mock_builder()
+            .plugin(Builder::new().backend(backend.clone()).timeout(50).build())
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let clipboard = app.clipboard_next();
+        let options = SnapshotOptions {
+            save_image: false,
+            ..SnapshotOptions::default()
+        };
+
+        let err =
+            block_on(clipboard.start_watch(app.handle().clone(), "main", Some(options), None))
+                .unwrap_err();
+        assert_eq!(err.code(), "timeout");
+
+        // waits for the timed out start and the stop queued behind it
+        backend.release();
+        block_on(clipboard.read_text(None)).unwrap_err();
+
+        assert!(!clipboard.watch_status().running);
+        assert!(block_on(clipboard.watch_owners.lock()).is_empty());
+    }
+}
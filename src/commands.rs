@@ -1,133 +1,329 @@
-use crate::models::*;
+use crate::subscription::SubscribeOptions;
 use crate::ClipboardNextExt;
-use crate::Result;
+use crate::{constants, Error, Result};
+use clipboard_next_core::{
+    ChangeFilter, ClipboardChange, ClipboardSnapshot, ClipboardStatus, FormatInfo,
+    OperationOptions, ReadFiles, ReadImage, Representation, SnapshotOptions, WatchStatus,
+};
 use std::path::PathBuf;
-use tauri::{command, AppHandle, Runtime};
+use tauri::ipc::{Channel, InvokeBody, Request, Response};
+use tauri::{command, AppHandle, Runtime, Window};
 
 /// Start listening for clipboard changes
 ///
+/// Every change event carries a snapshot of the clipboard contents. Calling it again from the same
+/// window does nothing, the watcher is released when the window is destroyed.
+///
+/// # Arguments
+/// * `options` - Optional formats to capture and where to save the image
+/// * `filter` - Optional formats and text sizes of the changes to emit, defaults to every change
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::start_watch;
-/// await start_watch(app_handle)?;
+/// await start_watch(app_handle, None, None)?;
 /// ```
 #[command]
-pub(crate) async fn start_watch<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    app.clipboard_next().start_watch(app.clone())
+pub(crate) async fn start_watch<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    options: Option<SnapshotOptions>,
+    filter: Option<ChangeFilter>,
+) -> Result<()> {
+    app.clipboard_next()
+        .start_watch(app.clone(), window.label(), options, filter)
+        .await
 }
 
 /// Stop listening for clipboard changes
 ///
+/// The watcher keeps running while other windows listen.
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::stop_watch;
 /// await stop_watch(app_handle)?;
 /// ```
 #[command]
-pub(crate) async fn stop_watch<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    app.clipboard_next().stop_watch()
+pub(crate) async fn stop_watch<R: Runtime>(app: AppHandle<R>, window: Window<R>) -> Result<()> {
+    app.clipboard_next().stop_watch(window.label()).await
+}
+
+/// Stream clipboard changes to the calling window only
+///
+/// Unlike `start_watch`, the changes are not emitted to other windows. The subscription is released
+/// when the window is destroyed.
+///
+/// # Arguments
+/// * `channel` - Channel the changes are sent to
+/// * `options` - Optional formats, filter, debounce interval and whether to send the contents
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::subscribe;
+/// let id = subscribe(app_handle, channel, None).await?;
+/// ```
+#[command]
+pub(crate) async fn subscribe<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    channel: Channel<ClipboardChange>,
+    options: Option<SubscribeOptions>,
+) -> Result<u64> {
+    app.clipboard_next()
+        .subscribe_channel(window.label(), channel, options)
+        .await
+}
+
+/// Stop a subscription made by the calling window
+///
+/// # Arguments
+/// * `id` - The id returned by `subscribe`
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::unsubscribe;
+/// unsubscribe(app_handle, id).await?;
+/// ```
+#[command]
+pub(crate) async fn unsubscribe<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+    id: u64,
+) -> Result<()> {
+    app.clipboard_next()
+        .unsubscribe_channel(window.label(), id)
+        .await
+}
+
+/// Stop reporting clipboard changes without stopping the watcher
+///
+/// # Arguments
+/// * `resume_after` - Optional time in milliseconds after which changes are reported again
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::pause_watch;
+/// pause_watch(app_handle, Some(60_000)).await?;
+/// ```
+#[command]
+pub(crate) async fn pause_watch<R: Runtime>(
+    app: AppHandle<R>,
+    resume_after: Option<u64>,
+) -> Result<()> {
+    app.clipboard_next().pause_watch(resume_after)
+}
+
+/// Report clipboard changes again after `pause_watch`
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::resume_watch;
+/// resume_watch(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn resume_watch<R: Runtime>(app: AppHandle<R>) -> Result<()> {
+    app.clipboard_next().resume_watch()
+}
+
+/// Get the state of the clipboard watcher
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::watch_status;
+/// let status = watch_status(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn watch_status<R: Runtime>(app: AppHandle<R>) -> Result<WatchStatus> {
+    Ok(app.clipboard_next().watch_status())
+}
+
+/// Get the latest clipboard change, `None` if there was none since the watcher started
+///
+/// Like the change events, it is `None` for a window whose filter does not match the change.
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::get_last_change;
+/// let change = get_last_change(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn get_last_change<R: Runtime>(
+    app: AppHandle<R>,
+    window: Window<R>,
+) -> Result<Option<ClipboardChange>> {
+    Ok(app.clipboard_next().last_change_for(window.label()))
 }
 
 /// Check if the clipboard contains plain text
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::has_text;
 /// let has = has_text(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn has_text<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.clipboard_next().has_text()
+pub(crate) async fn has_text<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_text(options).await
 }
 
 /// Check if the clipboard contains rich text
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::has_rtf;
 /// let has = has_rtf(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn has_rtf<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.clipboard_next().has_rtf()
+pub(crate) async fn has_rtf<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_rtf(options).await
 }
 
 /// Check if the clipboard contains html
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::has_html;
 /// let has = has_html(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn has_html<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.clipboard_next().has_html()
+pub(crate) async fn has_html<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_html(options).await
 }
 
 /// Check if the clipboard contains an image
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::has_image;
 /// let has = has_image(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn has_image<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.clipboard_next().has_image()
+pub(crate) async fn has_image<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_image(options).await
 }
 
 /// Check if the clipboard contains files
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::has_files;
 /// let has = has_files(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn has_files<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.clipboard_next().has_files()
+pub(crate) async fn has_files<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_files(options).await
+}
+
+/// Check if the clipboard contains a custom format
+///
+/// # Arguments
+/// * `format` - The format name, e.g. a MIME type such as `image/svg+xml`
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::has_format;
+/// let has = has_format(app_handle, "image/svg+xml".to_string()).await?;
+/// ```
+#[command]
+pub(crate) async fn has_format<R: Runtime>(
+    app: AppHandle<R>,
+    format: String,
+    options: Option<OperationOptions>,
+) -> Result<bool> {
+    app.clipboard_next().has_format(format, options).await
 }
 
 /// Read plain text from the clipboard
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::read_text;
 /// let text = read_text(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn read_text<R: Runtime>(app: AppHandle<R>) -> Result<String> {
-    app.clipboard_next().read_text()
+pub(crate) async fn read_text<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<String> {
+    app.clipboard_next().read_text(options).await
 }
 
 /// Read rich text from the clipboard
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::read_rtf;
 /// let rtf = read_rtf(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn read_rtf<R: Runtime>(app: AppHandle<R>) -> Result<String> {
-    app.clipboard_next().read_rtf()
+pub(crate) async fn read_rtf<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<String> {
+    app.clipboard_next().read_rtf(options).await
 }
 
 /// Read html from the clipboard
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::read_html;
 /// let html = read_html(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn read_html<R: Runtime>(app: AppHandle<R>) -> Result<String> {
-    app.clipboard_next().read_html()
+pub(crate) async fn read_html<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<String> {
+    app.clipboard_next().read_html(options).await
 }
 
 /// Read image from the clipboard
 ///
 /// # Arguments
 /// * `save_path` - Optional path to save the image file
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -138,26 +334,59 @@ pub(crate) async fn read_html<R: Runtime>(app: AppHandle<R>) -> Result<String> {
 pub(crate) async fn read_image<R: Runtime>(
     app: AppHandle<R>,
     save_path: Option<PathBuf>,
+    options: Option<OperationOptions>,
 ) -> Result<ReadImage> {
-    app.clipboard_next().read_image(app.clone(), save_path)
+    app.clipboard_next()
+        .read_image(app.clone(), save_path, options)
+        .await
 }
 
 /// Read file paths from the clipboard
 ///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::read_files;
 /// let files = read_files(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn read_files<R: Runtime>(app: AppHandle<R>) -> Result<ReadFiles> {
-    app.clipboard_next().read_files()
+pub(crate) async fn read_files<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<ReadFiles> {
+    app.clipboard_next().read_files(options).await
+}
+
+/// Read the raw bytes of a custom format from the clipboard
+///
+/// The bytes are returned as is, the frontend receives an `ArrayBuffer`.
+///
+/// # Arguments
+/// * `format` - The format name, e.g. a MIME type such as `image/svg+xml`
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::read_format;
+/// let svg = read_format(app_handle, "image/svg+xml".to_string()).await?;
+/// ```
+#[command]
+pub(crate) async fn read_format<R: Runtime>(
+    app: AppHandle<R>,
+    format: String,
+    options: Option<OperationOptions>,
+) -> Result<Response> {
+    let buffer = app.clipboard_next().read_format(format, options).await?;
+    Ok(Response::new(buffer))
 }
 
 /// Write plain text to the clipboard
 ///
 /// # Arguments
 /// * `content` - The text content to write
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -165,14 +394,19 @@ pub(crate) async fn read_files<R: Runtime>(app: AppHandle<R>) -> Result<ReadFile
 /// write_text(app_handle, "Hello, World!".to_string()).await?;
 /// ```
 #[command]
-pub(crate) async fn write_text<R: Runtime>(app: AppHandle<R>, content: String) -> Result<()> {
-    app.clipboard_next().write_text(content)
+pub(crate) async fn write_text<R: Runtime>(
+    app: AppHandle<R>,
+    content: String,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write_text(content, options).await
 }
 
 /// Write rich text to the clipboard
 ///
 /// # Arguments
 /// * `content` - The RTF content to write
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -180,14 +414,19 @@ pub(crate) async fn write_text<R: Runtime>(app: AppHandle<R>, content: String) -
 /// write_rtf(app_handle, "\x1b[1m\x1b[4m\x1b[31mHello, World!\x1b[0m".to_string()).await?;
 /// ```
 #[command]
-pub(crate) async fn write_rtf<R: Runtime>(app: AppHandle<R>, content: String) -> Result<()> {
-    app.clipboard_next().write_rtf(content)
+pub(crate) async fn write_rtf<R: Runtime>(
+    app: AppHandle<R>,
+    content: String,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write_rtf(content, options).await
 }
 
 /// Write html to the clipboard
 ///
 /// # Arguments
 /// * `content` - The HTML content to write
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -195,14 +434,19 @@ pub(crate) async fn write_rtf<R: Runtime>(app: AppHandle<R>, content: String) ->
 /// write_html(app_handle, "<p>Hello</p>".to_string()).await?;
 /// ```
 #[command]
-pub(crate) async fn write_html<R: Runtime>(app: AppHandle<R>, content: String) -> Result<()> {
-    app.clipboard_next().write_html(content)
+pub(crate) async fn write_html<R: Runtime>(
+    app: AppHandle<R>,
+    content: String,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write_html(content, options).await
 }
 
 /// Write an image to the clipboard from a file path
 ///
 /// # Arguments
 /// * `image_path` - Path to the image file
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -210,14 +454,19 @@ pub(crate) async fn write_html<R: Runtime>(app: AppHandle<R>, content: String) -
 /// write_image(app_handle, "/path/to/image.png".to_string()).await?;
 /// ```
 #[command]
-pub(crate) async fn write_image<R: Runtime>(app: AppHandle<R>, image_path: String) -> Result<()> {
-    app.clipboard_next().write_image(image_path)
+pub(crate) async fn write_image<R: Runtime>(
+    app: AppHandle<R>,
+    image_path: String,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write_image(image_path, options).await
 }
 
 /// Write file paths to the clipboard
 ///
 /// # Arguments
 /// * `files_path` - file paths
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
 ///
 /// # Example
 /// ```
@@ -228,20 +477,187 @@ pub(crate) async fn write_image<R: Runtime>(app: AppHandle<R>, image_path: Strin
 pub(crate) async fn write_files<R: Runtime>(
     app: AppHandle<R>,
     files_path: Vec<String>,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write_files(files_path, options).await
+}
+
+/// Write the raw bytes of a custom format to the clipboard
+///
+/// The bytes are the raw request body, the format name and the optional options are passed in the
+/// `clipboard-format` and `clipboard-options` headers.
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::write_format;
+/// write_format(app_handle, request).await?;
+/// ```
+#[command]
+pub(crate) async fn write_format<R: Runtime>(
+    app: AppHandle<R>,
+    request: Request<'_>,
 ) -> Result<()> {
-    app.clipboard_next().write_files(files_path)
+    let header = |name: &str| -> Result<Option<&str>> {
+        request
+            .headers()
+            .get(name)
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|_| Error::InvalidRequest(format!("{name} header is not ascii")))
+            })
+            .transpose()
+    };
+
+    let format = header(constants::header::FORMAT)?
+        .ok_or_else(|| {
+            Error::InvalidRequest(format!("missing {} header", constants::header::FORMAT))
+        })?
+        .to_string();
+
+    let options: Option<OperationOptions> = header(constants::header::OPTIONS)?
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|err| Error::InvalidRequest(err.to_string()))?;
+
+    // the postMessage IPC fallback sends the bytes as a JSON number array
+    let buffer = match request.body() {
+        InvokeBody::Raw(buffer) => buffer.clone(),
+        InvokeBody::Json(value) => serde_json::from_value(value.clone())
+            .map_err(|err| Error::InvalidRequest(err.to_string()))?,
+    };
+
+    app.clipboard_next()
+        .write_format(format, buffer, options)
+        .await
+}
+
+/// Write several representations of the same content to the clipboard at once
+///
+/// The clipboard is replaced in a single operation, e.g. html with a plain text alternative and an
+/// image.
+///
+/// # Arguments
+/// * `representations` - The text, html, rtf, image, files and custom format contents to write
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::write;
+/// write(app_handle, vec![Representation::Text { content: "Hello".to_string() }]).await?;
+/// ```
+#[command]
+pub(crate) async fn write<R: Runtime>(
+    app: AppHandle<R>,
+    representations: Vec<Representation>,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().write(representations, options).await
 }
 
 /// Clear the clipboard contents
 ///
+/// # Arguments
+/// * `options` - Optional timeout, retry policy and echo suppression, defaults to the plugin config
+///
 /// # Example
 /// ```
 /// use tauri_plugin_clipboard_next::clear;
 /// clear(app_handle).await?;
 /// ```
 #[command]
-pub(crate) async fn clear<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    app.clipboard_next().clear()
+pub(crate) async fn clear<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<()> {
+    app.clipboard_next().clear(options).await
+}
+
+/// Read every requested format from the clipboard at once
+///
+/// The contents are read in a single clipboard operation, so they cannot change in between.
+///
+/// # Arguments
+/// * `options` - Optional formats to read and where to save the image
+/// * `operation` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::read_all;
+/// let snapshot = read_all(app_handle, None, None).await?;
+/// ```
+#[command]
+pub(crate) async fn read_all<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<SnapshotOptions>,
+    operation: Option<OperationOptions>,
+) -> Result<ClipboardSnapshot> {
+    app.clipboard_next()
+        .read_all(app.clone(), options, operation)
+        .await
+}
+
+/// List every format the clipboard contains
+///
+/// Names are reported as the platform advertises them, e.g. MIME types on Linux, with the format
+/// they stand for where it is known.
+///
+/// # Arguments
+/// * `sizes` - Read every format except images to report its size in bytes, defaults to `false`
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::available_formats;
+/// let formats = available_formats(app_handle, Some(true)).await?;
+/// ```
+#[command]
+pub(crate) async fn available_formats<R: Runtime>(
+    app: AppHandle<R>,
+    sizes: Option<bool>,
+    options: Option<OperationOptions>,
+) -> Result<Vec<FormatInfo>> {
+    app.clipboard_next()
+        .available_formats(sizes.unwrap_or(false), options)
+        .await
+}
+
+/// Get the number of clipboard changes seen so far
+///
+/// The count only increases, comparing it with a previous value tells whether the clipboard changed.
+///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::change_count;
+/// let count = change_count(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn change_count<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<u64> {
+    app.clipboard_next().change_count(options).await
+}
+
+/// Get a hash of the clipboard contents across all formats
+///
+/// # Arguments
+/// * `options` - Optional timeout and retry policy, defaults to the plugin config
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::fingerprint;
+/// let fingerprint = fingerprint(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn fingerprint<R: Runtime>(
+    app: AppHandle<R>,
+    options: Option<OperationOptions>,
+) -> Result<String> {
+    app.clipboard_next().fingerprint(options).await
 }
 
 /// Get the file path for clipboard operations
@@ -257,3 +673,15 @@ pub(crate) async fn clear<R: Runtime>(app: AppHandle<R>) -> Result<()> {
 pub(crate) async fn get_file_path<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf> {
     app.clipboard_next().get_file_path(app.clone())
 }
+
+/// Get the availability of the clipboard backend
+///
+/// # Example
+/// ```
+/// use tauri_plugin_clipboard_next::status;
+/// let status = status(app_handle).await?;
+/// ```
+#[command]
+pub(crate) async fn status<R: Runtime>(app: AppHandle<R>) -> Result<ClipboardStatus> {
+    app.clipboard_next().status().await
+}
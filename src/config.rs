@@ -0,0 +1,34 @@
+use serde::Deserialize;
+
+/// `plugins.clipboard-next` section of `tauri.conf.json`
+///
+/// ```json
+/// {
+///   "plugins": {
+///     "clipboard-next": {
+///       "storageDir": "/path/to/save",
+///       "imageFormat": "jpeg",
+///       "autoStartWatch": true,
+///       "formats": ["text", "html", "image"],
+///       "limits": { "maxTextSize": 1048576, "maxImageSize": 67108864, "maxFiles": 100 },
+///       "debounceInterval": 100,
+///       "debounceMaxWait": 1000,
+///       "suppressEcho": true,
+///       "timeout": 2000,
+///       "retry": { "maxRetries": 3, "backoff": 50, "maxBackoff": 500 },
+///       "watchMode": "polling",
+///       "pollInterval": 1000,
+///       "backlog": 20,
+///       "watchSelections": ["clipboard", "primary"]
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    #[serde(flatten)]
+    pub clipboard: clipboard_next_core::Config,
+    /// Start watching clipboard changes when the plugin is initialized
+    pub auto_start_watch: bool,
+}
@@ -2,11 +2,29 @@
 pub mod plugin {
     /// plugin name
     pub const NAME: &str = "tauri-plugin-clipboard-next";
-    
+
     /// plugin file
     pub const FILE: &str = "file";
+
+    /// owner of the watcher started by `autoStartWatch`
+    pub const WATCH_OWNER: &str = "plugin:clipboard-next";
+
+    /// prefix of the owners of the watchers held by subscriptions
+    pub const SUBSCRIPTION_OWNER: &str = "plugin:clipboard-next:subscription";
 }
 
 pub mod event {
     pub const CLIPBOARD_CHANGE: &str = "plugin:clipboard-next://clipboard_change";
-}
\ No newline at end of file
+
+    /// emitted along with `CLIPBOARD_CHANGE` when the clipboard becomes empty
+    pub const CLIPBOARD_CLEARED: &str = "plugin:clipboard-next://clipboard_cleared";
+}
+
+/// headers of the commands taking a raw body
+pub mod header {
+    /// name of the custom format written by `write_format`
+    pub const FORMAT: &str = "clipboard-format";
+
+    /// JSON encoded `OperationOptions` of `write_format`
+    pub const OPTIONS: &str = "clipboard-options";
+}
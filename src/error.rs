@@ -1,2 +1,59 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
 /// Result alias
-pub type Result<T> = std::result::Result<T, String>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Plugin error
+///
+/// Serialized to the frontend as `{ code, message, details }`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Clipboard(#[from] clipboard_next_core::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+
+    /// The command was invoked with a missing or malformed argument
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+}
+
+impl Error {
+    /// Stable identifier of the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Clipboard(err) => err.code(),
+            Error::Io(_) => "io",
+            Error::Tauri(_) => "tauri",
+            Error::InvalidRequest(_) => "invalidRequest",
+        }
+    }
+
+    /// Additional machine-readable information, e.g. the missing format or the offending path
+    pub fn details(&self) -> Option<String> {
+        match self {
+            Error::Clipboard(err) => err.details(),
+            Error::Io(err) => Some(err.kind().to_string()),
+            Error::Tauri(_) => None,
+            Error::InvalidRequest(reason) => Some(reason.clone()),
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
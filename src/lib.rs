@@ -1,27 +1,37 @@
+use std::path::PathBuf;
 use tauri::{
-    plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    Manager, RunEvent, Runtime, WindowEvent,
 };
 
-pub use models::*;
-
 #[cfg(desktop)]
 mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+mod clipboard_next;
 mod commands;
+mod config;
+mod constants;
 mod error;
-mod models;
+mod subscription;
 mod utils;
-mod constants;
+mod writer;
 
+pub use clipboard_next_core::{
+    ChangeDelta, ChangeFilter, ChangeHandler, ChangeOrigin, ClipboardBackend, ClipboardChange,
+    ClipboardContent, ClipboardFormat, ClipboardNextManager, ClipboardSnapshot, ClipboardStatus,
+    ClipboardWorker, ContentFormat, FileItem, FormatInfo, ImageFormat, ImageInfo, Limits,
+    MemoryBackend, OperationOptions, ReadFiles, ReadImage, Representation, RetryPolicy,
+    RustImageData, Selection, SnapshotOptions, SystemBackend, WatchHandle, WatchHandler, WatchMode,
+    WatchStatus,
+};
+pub use config::Config;
 pub use error::*;
 
-#[cfg(desktop)]
-use desktop::ClipboardNext;
-#[cfg(mobile)]
-use mobile::ClipboardNext;
+pub use clipboard_next::ClipboardNext;
+pub use subscription::{ChangeCallback, SubscribeOptions, Subscription};
+pub use writer::Writer;
 
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the clipboard-next APIs.
 pub trait ClipboardNextExt<R: Runtime> {
@@ -35,38 +45,311 @@ impl<R: Runtime, T: Manager<R>> ClipboardNextExt<R> for T {
 }
 
 /// Initializes the plugin.
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("clipboard-next")
-        .invoke_handler(tauri::generate_handler![
-            commands::start_watch,
-            commands::stop_watch,
-            commands::has_text,
-            commands::has_rtf,
-            commands::has_html,
-            commands::has_image,
-            commands::has_files,
-            commands::read_text,
-            commands::read_rtf,
-            commands::read_html,
-            commands::read_image,
-            commands::read_files,
-            commands::write_text,
-            commands::write_rtf,
-            commands::write_html,
-            commands::write_image,
-            commands::write_files,
-            commands::clear,
-            commands::get_file_path,
-        ])
-        .setup(|app, api| {
-            #[cfg(mobile)]
-            let clipboard_next = mobile::init(app, api)?;
-
-            #[cfg(desktop)]
-            let clipboard_next = desktop::init(app, api)?;
-
-            app.manage(clipboard_next);
-            Ok(())
-        })
-        .build()
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    Builder::new().build()
+}
+
+/// Initializes the plugin with a custom clipboard backend, e.g. a [`MemoryBackend`] for tests.
+pub fn init_with_backend<R: Runtime, B: ClipboardBackend>(
+    backend: B,
+) -> TauriPlugin<R, Option<Config>> {
+    Builder::new().backend(backend).build()
+}
+
+/// Builder for the plugin
+///
+/// Options set on the builder override the `plugins.clipboard-next` section of `tauri.conf.json`.
+///
+/// ```rust,ignore
+/// tauri::Builder::default()
+///     .plugin(
+///         tauri_plugin_clipboard_next::Builder::new()
+///             .image_format(ImageFormat::Jpeg)
+///             .auto_start_watch(true)
+///             .build(),
+///     )
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    backend: Option<Box<dyn ClipboardBackend>>,
+    storage_dir: Option<PathBuf>,
+    image_format: Option<ImageFormat>,
+    auto_start_watch: Option<bool>,
+    formats: Option<Vec<ClipboardFormat>>,
+    limits: Option<Limits>,
+    debounce_interval: Option<u64>,
+    debounce_max_wait: Option<u64>,
+    suppress_echo: Option<bool>,
+    timeout: Option<u64>,
+    retry: Option<RetryPolicy>,
+    watch_mode: Option<WatchMode>,
+    poll_interval: Option<u64>,
+    backlog: Option<usize>,
+    watch_selections: Option<Vec<Selection>>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clipboard backend, defaults to the system clipboard
+    pub fn backend<B: ClipboardBackend>(mut self, backend: B) -> Self {
+        self.backend = Some(Box::new(backend));
+        self
+    }
+
+    /// Directory images are saved to when no path is given, must be absolute
+    pub fn storage_dir<P: Into<PathBuf>>(mut self, storage_dir: P) -> Self {
+        self.storage_dir = Some(storage_dir.into());
+        self
+    }
+
+    /// Format of the images saved by `read_image`
+    pub fn image_format(mut self, image_format: ImageFormat) -> Self {
+        self.image_format = Some(image_format);
+        self
+    }
+
+    /// Start watching clipboard changes when the plugin is initialized
+    pub fn auto_start_watch(mut self, auto_start_watch: bool) -> Self {
+        self.auto_start_watch = Some(auto_start_watch);
+        self
+    }
+
+    /// Formats that can be read and written, every other format is ignored
+    pub fn formats(mut self, formats: Vec<ClipboardFormat>) -> Self {
+        self.formats = Some(formats);
+        self
+    }
+
+    /// Size limits for reads and writes
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Clipboard changes closer together than this interval are reported once, in milliseconds
+    pub fn debounce_interval(mut self, debounce_interval: u64) -> Self {
+        self.debounce_interval = Some(debounce_interval);
+        self
+    }
+
+    /// Longest time a burst of changes is held back by the debounce interval, in milliseconds
+    pub fn debounce_max_wait(mut self, debounce_max_wait: u64) -> Self {
+        self.debounce_max_wait = Some(debounce_max_wait);
+        self
+    }
+
+    /// Drop changes caused by the app's own writes instead of reporting them with the `self` origin
+    pub fn suppress_echo(mut self, suppress_echo: bool) -> Self {
+        self.suppress_echo = Some(suppress_echo);
+        self
+    }
+
+    /// Time to wait for a clipboard operation before failing with a timeout, in milliseconds, `0` waits forever
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry policy for transient clipboard failures
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// How clipboard changes are detected, polling is used if the backend cannot watch by default
+    pub fn watch_mode(mut self, watch_mode: WatchMode) -> Self {
+        self.watch_mode = Some(watch_mode);
+        self
+    }
+
+    /// Interval between two checks of the polling watcher, in milliseconds
+    pub fn poll_interval(mut self, poll_interval: u64) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Number of recent changes kept for windows that start listening late, the latest is always kept
+    pub fn backlog(mut self, backlog: usize) -> Self {
+        self.backlog = Some(backlog);
+        self
+    }
+
+    /// Selections the watcher reports changes of, e.g. also the X11 `PRIMARY` selection on Linux
+    pub fn watch_selections(mut self, watch_selections: Vec<Selection>) -> Self {
+        self.watch_selections = Some(watch_selections);
+        self
+    }
+
+    fn apply(&mut self, config: &mut Config) {
+        if let Some(storage_dir) = self.storage_dir.take() {
+            config.clipboard.storage_dir = Some(storage_dir);
+        }
+        if let Some(image_format) = self.image_format.take() {
+            config.clipboard.image_format = image_format;
+        }
+        if let Some(auto_start_watch) = self.auto_start_watch.take() {
+            config.auto_start_watch = auto_start_watch;
+        }
+        if let Some(formats) = self.formats.take() {
+            config.clipboard.formats = formats;
+        }
+        if let Some(limits) = self.limits.take() {
+            config.clipboard.limits = limits;
+        }
+        if let Some(debounce_interval) = self.debounce_interval.take() {
+            config.clipboard.debounce_interval = debounce_interval;
+        }
+        if let Some(debounce_max_wait) = self.debounce_max_wait.take() {
+            config.clipboard.debounce_max_wait = debounce_max_wait;
+        }
+        if let Some(suppress_echo) = self.suppress_echo.take() {
+            config.clipboard.suppress_echo = suppress_echo;
+        }
+        if let Some(timeout) = self.timeout.take() {
+            config.clipboard.timeout = timeout;
+        }
+        if let Some(retry) = self.retry.take() {
+            config.clipboard.retry = retry;
+        }
+        if let Some(watch_mode) = self.watch_mode.take() {
+            config.clipboard.watch_mode = watch_mode;
+        }
+        if let Some(poll_interval) = self.poll_interval.take() {
+            config.clipboard.poll_interval = poll_interval;
+        }
+        if let Some(backlog) = self.backlog.take() {
+            config.clipboard.backlog = backlog;
+        }
+        if let Some(watch_selections) = self.watch_selections.take() {
+            config.clipboard.watch_selections = watch_selections;
+        }
+    }
+
+    pub fn build<R: Runtime>(mut self) -> TauriPlugin<R, Option<Config>> {
+        PluginBuilder::<R, Option<Config>>::new("clipboard-next")
+            .invoke_handler(tauri::generate_handler![
+                commands::start_watch,
+                commands::stop_watch,
+                commands::pause_watch,
+                commands::resume_watch,
+                commands::watch_status,
+                commands::get_last_change,
+                commands::subscribe,
+                commands::unsubscribe,
+                commands::has_text,
+                commands::has_rtf,
+                commands::has_html,
+                commands::has_image,
+                commands::has_files,
+                commands::has_format,
+                commands::read_text,
+                commands::read_rtf,
+                commands::read_html,
+                commands::read_image,
+                commands::read_files,
+                commands::read_format,
+                commands::read_all,
+                commands::write_text,
+                commands::write_rtf,
+                commands::write_html,
+                commands::write_image,
+                commands::write_files,
+                commands::write_format,
+                commands::write,
+                commands::clear,
+                commands::available_formats,
+                commands::change_count,
+                commands::fingerprint,
+                commands::get_file_path,
+                commands::status,
+            ])
+            .setup(move |app, api| {
+                let mut config = api.config().clone().unwrap_or_default();
+                self.apply(&mut config);
+                config.clipboard.validate()?;
+
+                let backend = match self.backend.take() {
+                    Some(backend) => backend,
+                    None => Box::new(SystemBackend::new()),
+                };
+
+                let manager = ClipboardNextManager::with_config(backend, config.clipboard);
+                let worker = ClipboardWorker::new(manager);
+
+                #[cfg(mobile)]
+                let clipboard_next = mobile::init(app, api, worker)?;
+
+                #[cfg(desktop)]
+                let clipboard_next = desktop::init(app, api, worker)?;
+
+                app.manage(clipboard_next);
+
+                if config.auto_start_watch {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        // a failed start is not fatal, the frontend can still call `start_watch`
+                        let _ = app
+                            .clipboard_next()
+                            .start_watch(app.clone(), constants::plugin::WATCH_OWNER, None, None)
+                            .await;
+                    });
+                }
+
+                Ok(())
+            })
+            .on_event(|app, event| {
+                let Some(clipboard_next) = app.try_state::<ClipboardNext<R>>() else {
+                    return;
+                };
+
+                match event {
+                    RunEvent::WindowEvent {
+                        label,
+                        event: WindowEvent::Destroyed,
+                        ..
+                    } => {
+                        let app = app.clone();
+                        let label = label.clone();
+
+                        tauri::async_runtime::spawn(async move {
+                            let _ = app.clipboard_next().stop_watch(&label).await;
+                            let _ = app.clipboard_next().unsubscribe_window(&label).await;
+                        });
+                    }
+                    RunEvent::Exit => clipboard_next.stop_all_watch(),
+                    _ => {}
+                }
+            })
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::async_runtime::block_on;
+    use tauri::test::{mock_builder, mock_context, noop_assets};
+
+    #[test]
+    fn init_with_backend_reads_and_writes_the_backend() {
+        let backend = MemoryBackend::new();
+        backend.set_external(vec![ClipboardContent::Text("theirs".into())]);
+
+        let app = mock_builder()
+            .plugin(init_with_backend(backend.clone()))
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let clipboard = app.clipboard_next();
+
+        assert_eq!(block_on(clipboard.read_text(None)).unwrap(), "theirs");
+
+        block_on(clipboard.write_text("mine".into(), None)).unwrap();
+        assert!(matches!(
+            backend.contents().as_slice(),
+            [ClipboardContent::Text(text)] if text == "mine"
+        ));
+    }
 }
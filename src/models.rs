@@ -1,25 +0,0 @@
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ReadImage {
-    pub path: PathBuf,
-    pub width: u32,
-    pub height: u32,
-    pub size: u64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FileItem {
-    pub path: String,
-    pub size: u64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ReadFiles {
-    pub files: Vec<FileItem>,
-    pub size: u64,
-}
@@ -0,0 +1,112 @@
+use crate::ClipboardNext;
+use clipboard_next_core::{ChangeFilter, ClipboardChange, ClipboardFormat};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{async_runtime, AppHandle, Manager, Runtime};
+
+/// Called with every clipboard change, on the clipboard worker thread
+pub type ChangeCallback = Arc<dyn Fn(&ClipboardChange) + Send + Sync + 'static>;
+
+/// Options of a subscription made from the frontend through the `subscribe` command
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubscribeOptions {
+    /// Formats reported to the subscriber, defaults to every format captured by the watcher
+    pub formats: Option<Vec<ClipboardFormat>>,
+    /// Changes sent to the subscriber, defaults to every change
+    pub filter: Option<ChangeFilter>,
+    /// Changes closer together than this interval (ms) are sent once, `0` sends every change
+    pub debounce: u64,
+    /// Send the clipboard contents, otherwise only the formats the clipboard contains
+    pub include_content: bool,
+    /// Send the recent changes kept by the plugin before the new ones
+    pub replay: bool,
+}
+
+impl Default for SubscribeOptions {
+    fn default() -> Self {
+        Self {
+            formats: None,
+            filter: None,
+            debounce: 0,
+            include_content: true,
+            replay: false,
+        }
+    }
+}
+
+impl SubscribeOptions {
+    /// Trim a change to what the subscriber asked for
+    pub(crate) fn apply(&self, change: &mut ClipboardChange) {
+        if let Some(formats) = &self.formats {
+            change.snapshot.retain_formats(formats);
+            change.delta.retain_formats(formats);
+        }
+
+        if !self.include_content {
+            change.snapshot.clear_content();
+        }
+    }
+}
+
+/// Callbacks registered through [`ClipboardNext::subscribe`]
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    callbacks: Mutex<HashMap<u64, ChangeCallback>>,
+    next_id: AtomicU64,
+}
+
+impl Subscribers {
+    pub(crate) fn insert(&self, callback: ChangeCallback) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.callbacks.lock().unwrap().insert(id, callback);
+        id
+    }
+
+    pub(crate) fn remove(&self, id: u64) -> bool {
+        self.callbacks.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Call every callback, outside the lock so that callbacks can subscribe and unsubscribe
+    pub(crate) fn notify(&self, change: &ClipboardChange) {
+        let callbacks: Vec<ChangeCallback> =
+            self.callbacks.lock().unwrap().values().cloned().collect();
+
+        for callback in callbacks {
+            callback(change);
+        }
+    }
+}
+
+/// Subscription streaming to a webview, released when the webview's window is destroyed
+pub(crate) struct ChannelSubscription<R: Runtime> {
+    pub(crate) label: String,
+    pub(crate) _subscription: Subscription<R>,
+}
+
+/// Subscription returned by [`ClipboardNext::subscribe`], unsubscribes when dropped
+pub struct Subscription<R: Runtime> {
+    pub(crate) id: u64,
+    pub(crate) app_handle: AppHandle<R>,
+}
+
+impl<R: Runtime> Subscription<R> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl<R: Runtime> Drop for Subscription<R> {
+    fn drop(&mut self) {
+        let id = self.id;
+        let app_handle = self.app_handle.clone();
+
+        async_runtime::spawn(async move {
+            if let Some(clipboard_next) = app_handle.try_state::<ClipboardNext<R>>() {
+                let _ = clipboard_next.unsubscribe(id).await;
+            }
+        });
+    }
+}
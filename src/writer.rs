@@ -0,0 +1,104 @@
+use crate::{ClipboardNext, Result};
+use clipboard_next_core::{OperationOptions, Representation};
+use tauri::Runtime;
+
+/// Builder of a write putting several representations on the clipboard at once
+///
+/// Created by [`ClipboardNext::writer`], nothing is written until [`Writer::commit`] is called.
+///
+/// ```rust,ignore
+/// app.clipboard_next()
+///     .writer()
+///     .html("<b>Hello</b>")
+///     .text("Hello")
+///     .image_path("/path/to/image.png")
+///     .commit()
+///     .await?;
+/// ```
+pub struct Writer<'a, R: Runtime> {
+    clipboard_next: &'a ClipboardNext<R>,
+    representations: Vec<Representation>,
+    options: Option<OperationOptions>,
+}
+
+impl<'a, R: Runtime> Writer<'a, R> {
+    pub(crate) fn new(clipboard_next: &'a ClipboardNext<R>) -> Self {
+        Self {
+            clipboard_next,
+            representations: Vec::new(),
+            options: None,
+        }
+    }
+
+    /// Plain text
+    pub fn text<S: Into<String>>(mut self, content: S) -> Self {
+        self.representations.push(Representation::Text {
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Rich text, no plain text alternative is added
+    pub fn rtf<S: Into<String>>(mut self, content: S) -> Self {
+        self.representations.push(Representation::Rtf {
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Html, no plain text alternative is added
+    pub fn html<S: Into<String>>(mut self, content: S) -> Self {
+        self.representations.push(Representation::Html {
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Image read from a file
+    pub fn image_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.representations.push(Representation::Image {
+            path: Some(path.into()),
+            bytes: None,
+        });
+        self
+    }
+
+    /// Image from encoded bytes, e.g. PNG
+    pub fn image_bytes<B: Into<Vec<u8>>>(mut self, bytes: B) -> Self {
+        self.representations.push(Representation::Image {
+            path: None,
+            bytes: Some(bytes.into()),
+        });
+        self
+    }
+
+    /// File paths
+    pub fn files<S: Into<String>>(mut self, paths: impl IntoIterator<Item = S>) -> Self {
+        self.representations.push(Representation::Files {
+            paths: paths.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Raw bytes of a custom format, e.g. a MIME type
+    pub fn format<S: Into<String>, B: Into<Vec<u8>>>(mut self, format: S, bytes: B) -> Self {
+        self.representations.push(Representation::Custom {
+            format: format.into(),
+            bytes: bytes.into(),
+        });
+        self
+    }
+
+    /// Timeout, retry policy and echo suppression, defaults to the plugin config
+    pub fn options(mut self, options: OperationOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Replace the clipboard contents with every representation at once
+    pub async fn commit(self) -> Result<()> {
+        self.clipboard_next
+            .write(self.representations, self.options)
+            .await
+    }
+}